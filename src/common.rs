@@ -1,28 +1,57 @@
 mod action;
+mod animation;
+mod autosave;
 mod clipboard;
+mod color;
 mod config;
+mod grid;
+mod guides;
 mod help;
 mod input;
 mod mode;
+mod onion;
+mod palette_file;
+mod playback;
 mod program;
+mod remap;
 mod scene;
 mod selected_info;
 mod sprite;
+mod style;
+mod symmetry;
 mod texel;
+mod timeline;
+mod wand;
 
 pub mod fio; // file io
 
-pub use action::{Action, Layout, MetadataType, LAYOUT_WORDS, METADATA_TYPES};
+pub use action::{
+    Action, Alignment, Anchor, Distribution, FrameOp, Layout, MetadataType, RgbTarget, ALIGNMENT_WORDS, ANCHOR_WORDS,
+    DISTRIBUTE_WORDS, FRAME_WORDS, LAYOUT_WORDS, METADATA_TYPES,
+};
+pub use animation::{Animation, LoopMode, LOOP_MODE_WORDS};
+pub use autosave::{Autosave, RECOVER_WORDS};
 pub use clipboard::{Clipboard, ClipboardOp};
+pub use color::{ColorDepth, ColorTable, Rgb, DEPTH_WORDS};
 pub use config::{Config, ConfigV2};
+pub use grid::{Grid, GridChange, GRID_WORDS};
+pub use guides::{GuideChange, Guides, GUIDE_WORDS};
 pub use help::*;
 pub use input::*;
 pub use mode::{Mode, OnQuit, SelectMode};
+pub use onion::{OnionSkin, ONION_WORDS};
+pub use palette_file::{PaletteFile, PaletteFormat, PaletteOp, PALETTE_WORDS};
+pub use playback::{Playback, PLAYBACK_WORDS};
 pub use program::run;
-pub use scene::{Scene, SceneExt};
+pub use remap::{ColorRemap, RemapOp, RemapSource, Remaps, REMAP_WORDS};
+pub use scene::{world_bookmarks, world_groups, ObjectInfo, Scene, SceneExt, SceneV3};
 pub use selected_info::{SelectedInfo, SELECTED_INFO_TEMPLATE};
 pub use sprite::SpriteExt;
+pub use style::{ExtStyle, ExtStyleMap, ExtStyles};
+pub use symmetry::{mirror_symbol, Symmetry, SymmetryAxis, SYMMETRY_WORDS};
 pub use texel::TexelExt;
+pub use timeline::{Keyframes, TimelineChange, KEY_WORDS};
+pub use wand::{apply_mask_op, MagicWand, MaskOp, WandMatch, WAND_WORDS};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Error {
@@ -1,31 +1,159 @@
 use crate::common::fio::ExportFormat;
-use crate::common::{ClipboardOp, Error, Mode, OnQuit};
+use crate::common::{
+    ClipboardOp, ColorDepth, Error, ExtStyle, GridChange, GuideChange, LoopMode, MagicWand, MaskOp, Mode, OnQuit,
+    OnionSkin, PaletteOp, Playback, RemapOp, Rgb, Symmetry, TimelineChange,
+};
 use std::collections::HashMap;
-use texel_types::{ColorMode, Position2D, SymbolStyle, Translation, Which};
+use texel_types::{ColorMode, Dimension, Position2D, SymbolStyle, Translation, Which};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RgbTarget {
+    Color(ColorMode), // selected fg/bg color
+    Palette(usize),   // color palette entry
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameOp {
+    Duplicate,
+    Move(bool),         // right if true
+    Swap(usize, usize), // zero based indexes
+    Insert(usize),      // blank frame at zero based index
+    Copy,
+    Paste, // clipboard frame after current one
+}
+
+pub const FRAME_WORDS: [&str; 7] = ["dup", "left", "right", "swap", "insert", "copy", "paste"];
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Layout {
     None,
     Column(usize, (u16, u16)), // number of columns, padding size x, y
+    Row(u16),                  // padding size
+    Grid(usize, (u16, u16)),   // number of columns, padding size x, y, uniform cell size
     Random,
+    Align(Alignment, bool),         // alignment and "relative to viewport"
+    Distribute(bool, Distribution), // "horizontal" and distribution type
 }
 
 impl From<&str> for Layout {
     fn from(source: &str) -> Self {
         match source {
             "column" => Layout::Column(0, (0, 0)),
+            "row" => Layout::Row(0),
+            "grid" => Layout::Grid(0, (0, 0)),
             "random" => Layout::Random,
             _ => Layout::None,
         }
     }
 }
 
-pub const LAYOUT_WORDS: [&str; 2] = ["column", "random"];
+pub const LAYOUT_WORDS: [&str; 4] = ["column", "row", "grid", "random"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alignment {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    CenterH, // same center column
+    CenterV, // same center row
+}
+
+impl Alignment {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "left" => Some(Alignment::Left),
+            "right" => Some(Alignment::Right),
+            "top" => Some(Alignment::Top),
+            "bottom" => Some(Alignment::Bottom),
+            "center_h" => Some(Alignment::CenterH),
+            "center_v" => Some(Alignment::CenterV),
+            _ => None,
+        }
+    }
+}
+
+pub const ALIGNMENT_WORDS: [&str; 6] = ["left", "right", "top", "bottom", "center_h", "center_v"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Distribution {
+    Even,         // equal gaps between first and last object
+    Spacing(u16), // fixed gap between objects
+    Pitch(u16),   // fixed distance between object starts
+}
+
+pub const DISTRIBUTE_WORDS: [&str; 4] = ["horizontal", "vertical", "spacing", "pitch"];
+
+// canvas anchor for resizing, which part of the sprite stays in place
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Anchor {
+    #[default]
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "top_left" => Some(Anchor::TopLeft),
+            "top" => Some(Anchor::Top),
+            "top_right" => Some(Anchor::TopRight),
+            "left" => Some(Anchor::Left),
+            "center" => Some(Anchor::Center),
+            "right" => Some(Anchor::Right),
+            "bottom_left" => Some(Anchor::BottomLeft),
+            "bottom" => Some(Anchor::Bottom),
+            "bottom_right" => Some(Anchor::BottomRight),
+            _ => None,
+        }
+    }
+
+    // offset of the new area of size "to" inside of the old area of size "from"
+    pub fn offset(self, from: Dimension, to: Dimension) -> Position2D {
+        let diff_x = i32::from(from.w) - i32::from(to.w);
+        let diff_y = i32::from(from.h) - i32::from(to.h);
+
+        let x = match self {
+            Anchor::TopLeft | Anchor::Left | Anchor::BottomLeft => 0,
+            Anchor::Top | Anchor::Center | Anchor::Bottom => diff_x / 2,
+            Anchor::TopRight | Anchor::Right | Anchor::BottomRight => diff_x,
+        };
+        let y = match self {
+            Anchor::TopLeft | Anchor::Top | Anchor::TopRight => 0,
+            Anchor::Left | Anchor::Center | Anchor::Right => diff_y / 2,
+            Anchor::BottomLeft | Anchor::Bottom | Anchor::BottomRight => diff_y,
+        };
+
+        Position2D { x, y }
+    }
+}
+
+pub const ANCHOR_WORDS: [&str; 9] = [
+    "top_left",
+    "top",
+    "top_right",
+    "left",
+    "center",
+    "right",
+    "bottom_left",
+    "bottom",
+    "bottom_right",
+];
 
 #[derive(Debug)]
 pub enum MetadataType {
     Id(Option<u32>),
     Labels(HashMap<String, String>),
+    Duration(Option<u16>),                 // current frame duration in ms
+    Loop(LoopMode),                        // animation loop mode
+    Range(String, Option<(usize, usize)>), // named animation frame range, zero based
 }
 
 impl MetadataType {
@@ -33,6 +161,43 @@ impl MetadataType {
         matches!(self, Self::Id(_))
     }
 
+    pub fn is_animation(&self) -> bool {
+        matches!(self, Self::Duration(_) | Self::Loop(_) | Self::Range(_, _))
+    }
+
+    pub fn parse_duration(source: &str) -> Result<Self, Error> {
+        match source {
+            "none" => Ok(MetadataType::Duration(None)),
+            _ => Ok(MetadataType::Duration(Some(source.parse()?))),
+        }
+    }
+
+    pub fn parse_loop(source: &str) -> Result<Self, Error> {
+        LoopMode::parse(source)
+            .map(MetadataType::Loop)
+            .ok_or(Error::InvalidParam("Invalid loop mode"))
+    }
+
+    // frame ranges are given as shown in the status line, starting at 1
+    pub fn parse_range(name: &str, source: &str) -> Result<Self, Error> {
+        if source == "none" {
+            return Ok(MetadataType::Range(name.into(), None));
+        }
+
+        let mut parts = source.split('-').map(|s| s.trim());
+        let from: usize = parts.next().unwrap_or("").parse()?;
+        let to: usize = match parts.next() {
+            Some(to) => to.parse()?,
+            None => from,
+        };
+
+        if from == 0 || to < from {
+            return Err(Error::InvalidParam("Invalid frame range"));
+        }
+
+        Ok(MetadataType::Range(name.into(), Some((from - 1, to - 1))))
+    }
+
     pub fn parse_id(source: &str) -> Result<Self, Error> {
         match source {
             "none" => Ok(MetadataType::Id(None)),
@@ -55,7 +220,7 @@ impl MetadataType {
     }
 }
 
-pub const METADATA_TYPES: [&str; 2] = ["id", "labels"];
+pub const METADATA_TYPES: [&str; 5] = ["id", "labels", "duration", "loop", "anim"];
 
 #[derive(Debug, Default)]
 pub enum Action {
@@ -75,27 +240,62 @@ pub enum Action {
     PickColor(ColorMode),
     SwapColor,
     ApplyColor(ColorMode),
+    SetRgb(Rgb, RgbTarget), // exact color through scene color table
+    Palette(PaletteOp),
+    Quantize(ColorDepth),        // remaps selected sprites to reduced colors
+    Preview(Option<ColorDepth>), // sets or disables reduced color preview
+    Remap(RemapOp),
     ApplySymbol(char),
     ApplyStyle(SymbolStyle),
+    ApplyExtStyle(ExtStyle),   // styles kept per object next to the sprite
     ApplyRegion,               // takes "selected region" and translates into selection on objects
     SelectFrame(Which<usize>), // next/prev + index into number
     DeleteFrame,
     NewFrame,
+    Frame(FrameOp),
+    Timeline(TimelineChange),              // keyframes and scrubbing
     SelectObject(Which<Position2D>, bool), // select next keeping old if true
     SelectRegion,
     Read(String),
     Write(Option<String>),
     WriteAndQuit(Option<String>),
-    Export(ExportFormat, String),
+    Export(ExportFormat, String, bool, Option<ColorDepth>), // format, path, "include hidden" and target depth
     Translate(Translation),
     Layout(Layout),
     Delete,
     Undo,
     Redo,
+    UndoList(Option<usize>), // undo tree view at given or current state
+    UndoTo(usize),           // jumps to state of the undo tree
+    Recover(bool),           // restores scene from swap file, discards it if true
     ShowHelp(usize),
     Bookmark(usize, bool), // index and "set"
     Tutorial,
-    ClearBlank, // clears "blank" texels from sprite/selection
+    ClearBlank,                 // clears "blank" texels from sprite/selection
+    Symmetry(Option<Symmetry>), // sets or disables mirrored drawing
+    ToggleSymmetry,
+    OnionSkin(Option<OnionSkin>), // sets or disables onion skin in edit modes
+    ToggleOnionSkin,
+    Outline(char),            // outline symbol
+    Shadow(Position2D, bool), // offset and "as new sprite"
+    MagicWand(MaskOp),        // select matching texels under cursor into mask
+    SetWand(MagicWand),
+    Trim,
+    Canvas(Dimension, Anchor),
+    Group,      // groups selected objects
+    Ungroup,    // dissolves selected groups
+    EnterGroup, // edit children of selected group
+    LeaveGroup, // return to parent group
+    Hide,       // hides selected objects
+    Show,       // shows all hidden objects
+    Lock,       // locks selected objects
+    Unlock,     // unlocks all locked objects
+    Grid(GridChange),
+    Guide(GuideChange),
+    ToggleRulers,
+    ToggleCrosshair,
+    Play(Playback), // starts animation playback
+    Tick,           // timed playback step
 }
 
 impl From<&str> for Action {
@@ -112,13 +312,46 @@ impl From<&str> for Action {
             "quit!" | "q!" => Action::SetMode(Mode::Quitting(OnQuit::Force)),
             "x" => Action::WriteAndQuit(None),
             "help" | "h" => Action::ShowHelp(0),
-            "export" => Action::Export(ExportFormat::default(), String::default()),
+            "export" => Action::Export(ExportFormat::default(), String::default(), false, None),
             "tutorial" => Action::Tutorial,
             "clear_blank" => Action::ClearBlank,
             "duplicate" => Action::Duplicate(1),
             "layout" => Action::Layout(Layout::None),
+            "align" => Action::Layout(Layout::Align(Alignment::Left, false)),
+            "distribute" => Action::Layout(Layout::Distribute(true, Distribution::Even)),
             "set" => Action::SetMetadata(MetadataType::Id(None)),
             "metadata" => Action::ToggleMetadata,
+            "symmetry" | "sym" => Action::ToggleSymmetry,
+            "onion" => Action::ToggleOnionSkin,
+            "frame" => Action::Frame(FrameOp::Duplicate),
+            "key" => Action::Timeline(TimelineChange::Key),
+            "tick" => Action::Timeline(TimelineChange::Scrub(0, false)),
+            "palette" => Action::Palette(PaletteOp::Load(String::new())),
+            "rgb" => Action::SetRgb(Rgb { r: 0, g: 0, b: 0 }, RgbTarget::Color(ColorMode::Fg)),
+            "quantize" => Action::Quantize(ColorDepth::Ansi16),
+            "preview" => Action::Preview(None),
+            "remap" => Action::Remap(RemapOp::Delete(String::new())),
+            "outline" => Action::Outline('#'),
+            "shadow" => Action::Shadow(Position2D::from_xy(1, 1), false),
+            "wand" => Action::SetWand(MagicWand::default()),
+            "trim" => Action::Trim,
+            "canvas" => Action::Canvas(Dimension::default(), Anchor::default()),
+            "group" => Action::Group,
+            "ungroup" => Action::Ungroup,
+            "enter" => Action::EnterGroup,
+            "leave" => Action::LeaveGroup,
+            "hide" => Action::Hide,
+            "show" => Action::Show,
+            "lock" => Action::Lock,
+            "unlock" => Action::Unlock,
+            "grid" => Action::Grid(GridChange::Toggle),
+            "snap" => Action::Grid(GridChange::ToggleSnap),
+            "guide" => Action::Guide(GuideChange::Clear),
+            "rulers" => Action::ToggleRulers,
+            "crosshair" => Action::ToggleCrosshair,
+            "play" => Action::Play(Playback::default()),
+            "undolist" => Action::UndoList(None),
+            "recover" => Action::Recover(false),
             _ => Action::None,
         }
     }
@@ -138,8 +371,19 @@ impl Action {
         !matches!(self, Self::None)
     }
 
+    // variant name, describes changes in undo history
+    pub fn name(&self) -> String {
+        let debug = format!("{:?}", self);
+
+        debug
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .unwrap_or_default()
+            .to_string()
+    }
+
     pub fn complete_word(part: &str) -> Option<&'static str> {
-        const ACTION_WORDS: [&str; 16] = [
+        const ACTION_WORDS: [&str; 49] = [
             "new",
             "read",
             "write",
@@ -154,10 +398,43 @@ impl Action {
             "clear_blank",
             "duplicate",
             "layout",
+            "align",
+            "distribute",
             "set",
             "metadata",
+            "symmetry",
+            "onion",
+            "frame",
+            "key",
+            "tick",
+            "rgb",
+            "palette",
+            "quantize",
+            "preview",
+            "remap",
+            "outline",
+            "shadow",
+            "wand",
+            "trim",
+            "canvas",
+            "group",
+            "ungroup",
+            "enter",
+            "leave",
+            "hide",
+            "show",
+            "lock",
+            "unlock",
+            "grid",
+            "snap",
+            "guide",
+            "rulers",
+            "crosshair",
+            "play",
+            "undolist",
+            "recover",
         ];
 
-        return ACTION_WORDS.iter().find(|&word| word.starts_with(part)).copied();
+        ACTION_WORDS.iter().find(|&word| word.starts_with(part)).copied()
     }
 }
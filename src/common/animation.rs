@@ -0,0 +1,101 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum LoopMode {
+    #[default]
+    Loop,
+    PingPong,
+    Once,
+}
+
+impl LoopMode {
+    pub fn parse(source: &str) -> Option<Self> {
+        match source {
+            "loop" => Some(LoopMode::Loop),
+            "pingpong" => Some(LoopMode::PingPong),
+            "once" => Some(LoopMode::Once),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LoopMode::Loop => "loop",
+            LoopMode::PingPong => "pingpong",
+            LoopMode::Once => "once",
+        }
+    }
+}
+
+pub const LOOP_MODE_WORDS: [&str; 3] = ["loop", "pingpong", "once"];
+
+// per sprite animation metadata, frame indexes are zero based
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Animation {
+    #[serde(default)]
+    pub durations: Vec<Option<u16>>, // per frame in ms, playback speed if none
+    #[serde(default)]
+    pub loop_mode: LoopMode,
+    #[serde(default)]
+    pub ranges: BTreeMap<String, (usize, usize)>, // named inclusive frame ranges
+}
+
+impl Animation {
+    pub fn duration(&self, frame: usize) -> Option<u16> {
+        self.durations.get(frame).copied().flatten()
+    }
+
+    pub fn set_duration(&mut self, frame: usize, duration: Option<u16>) {
+        if self.durations.len() <= frame {
+            self.durations.resize(frame + 1, None);
+        }
+
+        self.durations[frame] = duration;
+
+        while self.durations.last() == Some(&None) {
+            self.durations.pop();
+        }
+    }
+
+    pub fn set_range(&mut self, name: &str, range: Option<(usize, usize)>) {
+        match range {
+            Some(range) => self.ranges.insert(name.into(), range),
+            None => self.ranges.remove(name),
+        };
+    }
+
+    // keeps durations aligned with frames, new frame copies the one at index
+    pub fn frame_inserted(&mut self, index: usize) {
+        if index < self.durations.len() {
+            self.durations.insert(index, self.durations[index]);
+        }
+    }
+
+    pub fn blank_frame_inserted(&mut self, index: usize) {
+        if index < self.durations.len() {
+            self.durations.insert(index, None);
+        }
+    }
+
+    pub fn frames_swapped(&mut self, a: usize, b: usize) {
+        let (da, db) = (self.duration(a), self.duration(b));
+
+        self.set_duration(a, db);
+        self.set_duration(b, da);
+    }
+
+    pub fn frame_removed(&mut self, index: usize) {
+        if index < self.durations.len() {
+            self.durations.remove(index);
+        }
+    }
+
+    pub fn ranges_str(&self) -> String {
+        self.ranges
+            .iter()
+            .map(|(name, (from, to))| format!("{}={}-{}", name, from + 1, to + 1))
+            .collect::<Vec<String>>()
+            .join(",")
+    }
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, Instant};
+
+pub const RECOVER_WORDS: [&str; 1] = ["discard"];
+
+// swap file triggers, 0 disables either one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Autosave {
+    pub changes: usize, // swap after this many changes
+    pub seconds: u64,   // or this long after the first unswapped change
+}
+
+impl Default for Autosave {
+    fn default() -> Self {
+        Autosave {
+            changes: 20,
+            seconds: 30,
+        }
+    }
+}
+
+impl Autosave {
+    // time left until swap is due, none if nothing is pending or triggers are off
+    pub fn due_in(&self, pending: usize, since: Option<Instant>) -> Option<Duration> {
+        if pending == 0 {
+            return None;
+        }
+
+        if self.changes > 0 && pending >= self.changes {
+            return Some(Duration::from_secs(0));
+        }
+
+        match since {
+            Some(since) if self.seconds > 0 => Some(Duration::from_secs(self.seconds).saturating_sub(since.elapsed())),
+            _ => None,
+        }
+    }
+}
@@ -14,6 +14,7 @@ pub enum Clipboard {
     Empty,
     Sprites(Vec<Sprite>),
     Texels(Texels),
+    Frame(Texels), // whole sprite frame
 }
 
 impl From<Clipboard> for Texels {
@@ -25,6 +26,7 @@ impl From<Clipboard> for Texels {
                 .flat_map(|s| s.into_iter()) // turn each sprite into iterator over active frame's texels
                 .collect(), // collect all texels from resulting set into Vec<Texel>
             Clipboard::Texels(texels) => texels,
+            Clipboard::Frame(texels) => texels,
         }
     }
 }
@@ -35,6 +37,7 @@ impl From<Clipboard> for Vec<Sprite> {
             Clipboard::Empty => Vec::new(),
             Clipboard::Sprites(sprites) => sprites,
             Clipboard::Texels(texels) => vec![Sprite::from_texels(texels)],
+            Clipboard::Frame(texels) => vec![Sprite::from_texels(texels)],
         }
     }
 }
@@ -0,0 +1,237 @@
+use crate::common::Error;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+// 24-bit color
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rgb {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+}
+
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+const SYSTEM_COLORS: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (128, 0, 0),
+    (0, 128, 0),
+    (128, 128, 0),
+    (0, 0, 128),
+    (128, 0, 128),
+    (0, 128, 128),
+    (192, 192, 192),
+    (128, 128, 128),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (0, 0, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+// reduced color sets of older terminals and consoles
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi8,
+    Mono,
+}
+
+pub const DEPTH_WORDS: [&str; 4] = ["16", "8", "mono", "off"];
+
+const ANSI_16: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15];
+const ANSI_8: [u8; 8] = [0, 1, 2, 3, 4, 5, 6, 7];
+const MONO: [u8; 2] = [0, 15];
+
+impl ColorDepth {
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        match source {
+            "16" => Ok(ColorDepth::Ansi16),
+            "8" => Ok(ColorDepth::Ansi8),
+            "mono" | "2" => Ok(ColorDepth::Mono),
+            _ => Err(Error::InvalidParam("Invalid color depth, use 16, 8 or mono")),
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ColorDepth::Ansi16 => "16",
+            ColorDepth::Ansi8 => "8",
+            ColorDepth::Mono => "mono",
+        }
+    }
+
+    fn targets(self) -> &'static [u8] {
+        match self {
+            ColorDepth::Ansi16 => &ANSI_16,
+            ColorDepth::Ansi8 => &ANSI_8,
+            ColorDepth::Mono => &MONO,
+        }
+    }
+
+    // default bg and fg are plain 256 color indexes too, they don't fit smaller depths
+    pub fn fits(self, color: u8) -> bool {
+        self.targets().contains(&color)
+    }
+
+    // nearest perceptual color of this depth, scene colors use their exact value
+    pub fn quantize(self, color: u8, colors: &ColorTable) -> u8 {
+        if self.fits(color) {
+            return color;
+        }
+
+        let rgb = colors.get(color).unwrap_or_else(|| Rgb::from_ansi(color));
+
+        self.targets()
+            .iter()
+            .copied()
+            .min_by_key(|c| rgb.perceptual_distance(Rgb::from_ansi(*c)))
+            .unwrap_or_default()
+    }
+}
+
+// indexes a scene color can take over, 16 is the default background
+const FIRST_ASSIGNABLE: u8 = 17;
+const LAST_ASSIGNABLE: u8 = 231;
+
+impl Rgb {
+    // #rrggbb or rrggbb
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let hex = source.trim_start_matches('#');
+
+        if hex.len() != 6 || !hex.is_ascii() {
+            return Err(Error::InvalidParam("Invalid RGB color, use #rrggbb"));
+        }
+
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16).map_err(|_| Error::InvalidParam("Invalid RGB color, use #rrggbb"))
+        };
+
+        Ok(Rgb {
+            r: channel(0)?,
+            g: channel(2)?,
+            b: channel(4)?,
+        })
+    }
+
+    // standard xterm values of ANSI-256 colors
+    pub fn from_ansi(color: u8) -> Self {
+        let (r, g, b) = match color {
+            0..=15 => SYSTEM_COLORS[usize::from(color)],
+            16..=231 => {
+                let base = color - 16;
+                (
+                    CUBE_LEVELS[usize::from(base / 36)],
+                    CUBE_LEVELS[usize::from((base / 6) % 6)],
+                    CUBE_LEVELS[usize::from(base % 6)],
+                )
+            }
+            _ => {
+                let level = 8 + (color - 232) * 10;
+                (level, level, level)
+            }
+        };
+
+        Rgb { r, g, b }
+    }
+
+    pub fn nearest_ansi(&self) -> u8 {
+        nearest_of(*self, 16..=255)
+    }
+
+    fn distance(&self, other: Rgb) -> u32 {
+        let dr = i32::from(self.r) - i32::from(other.r);
+        let dg = i32::from(self.g) - i32::from(other.g);
+        let db = i32::from(self.b) - i32::from(other.b);
+
+        (dr * dr + dg * dg + db * db) as u32
+    }
+
+    // "redmean" weighted distance, closer to how we see than plain RGB distance
+    fn perceptual_distance(&self, other: Rgb) -> u32 {
+        let rmean = (i32::from(self.r) + i32::from(other.r)) / 2;
+        let dr = i32::from(self.r) - i32::from(other.r);
+        let dg = i32::from(self.g) - i32::from(other.g);
+        let db = i32::from(self.b) - i32::from(other.b);
+
+        ((((512 + rmean) * dr * dr) >> 8) + 4 * dg * dg + (((767 - rmean) * db * db) >> 8)) as u32
+    }
+}
+
+impl std::fmt::Display for Rgb {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+    }
+}
+
+fn nearest_of(rgb: Rgb, candidates: impl Iterator<Item = u8>) -> u8 {
+    candidates
+        .min_by_key(|c| rgb.distance(Rgb::from_ansi(*c)))
+        .unwrap_or_default()
+}
+
+// scene colors, texels keep ANSI-256 indexes and the table maps some of them
+// to exact RGB values. Terminals without truecolor show the index as-is which
+// is the nearest ANSI color not otherwise in use at the time of assignment.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColorTable {
+    colors: BTreeMap<u8, Rgb>,
+}
+
+impl ColorTable {
+    pub fn get(&self, index: u8) -> Option<Rgb> {
+        self.colors.get(&index).copied()
+    }
+
+    pub fn index_of(&self, rgb: Rgb) -> Option<u8> {
+        self.colors.iter().find(|(_, c)| **c == rgb).map(|(index, _)| *index)
+    }
+
+    // index representing given color, takes over the nearest index nothing uses if new
+    pub fn assign(&mut self, rgb: Rgb, used: &BTreeSet<u8>) -> Result<u8, Error> {
+        if let Some(index) = self.index_of(rgb) {
+            return Ok(index);
+        }
+
+        let free = |colors: &BTreeMap<u8, Rgb>| {
+            (FIRST_ASSIGNABLE..=LAST_ASSIGNABLE).find(|i| !colors.contains_key(i) && !used.contains(i))
+        };
+
+        // full, drop entries no texel or palette refers to anymore
+        if free(&self.colors).is_none() {
+            self.colors.retain(|index, _| used.contains(index));
+        }
+
+        if free(&self.colors).is_none() {
+            return Err(Error::execution("Scene color table is full"));
+        }
+
+        let candidates =
+            (FIRST_ASSIGNABLE..=LAST_ASSIGNABLE).filter(|i| !self.colors.contains_key(i) && !used.contains(i));
+        let index = nearest_of(rgb, candidates);
+
+        self.colors.insert(index, rgb);
+
+        Ok(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use texel_types::{DEFAULT_BG_U8, DEFAULT_FG_U8};
+
+    #[test]
+    fn quantizes_default_colors() {
+        let colors = ColorTable::default();
+
+        for depth in [ColorDepth::Ansi16, ColorDepth::Ansi8, ColorDepth::Mono].iter() {
+            assert!(!depth.fits(DEFAULT_BG_U8));
+            assert!(!depth.fits(DEFAULT_FG_U8));
+            assert!(depth.fits(depth.quantize(DEFAULT_BG_U8, &colors)));
+            assert!(depth.fits(depth.quantize(DEFAULT_FG_U8, &colors)));
+        }
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::{CharMap, Error, ModesCharMap};
+use crate::common::{Autosave, CharMap, Error, ModesCharMap, Remaps};
 use crate::resources::{ColorPalette, SymbolPalette};
 use serde::{Deserialize, Serialize};
 use std::path::Path;
@@ -54,6 +54,14 @@ pub struct ConfigV2 {
     pub color_palette: ColorPalette,
     pub symbol_palette: SymbolPalette,
     pub char_map: ModesCharMap,
+    #[serde(default)]
+    pub remaps: Remaps,
+    #[serde(default)]
+    pub history_budget: Option<usize>, // bytes kept for undo/redo, built-in default if none
+    #[serde(default)]
+    pub persistent_undo: Option<usize>, // bytes of undo history saved next to scenes, off if none
+    #[serde(default)]
+    pub autosave: Autosave, // swap file triggers
 }
 
 fn upgrade_v1_to_v2(v1: ConfigV1) -> ConfigV2 {
@@ -61,6 +69,10 @@ fn upgrade_v1_to_v2(v1: ConfigV1) -> ConfigV2 {
         color_palette: v1.color_palette,
         symbol_palette: v1.symbol_palette,
         char_map: ModesCharMap::from(v1.char_map),
+        remaps: Remaps::default(),
+        history_budget: None,
+        persistent_undo: None,
+        autosave: Autosave::default(),
     }
 }
 
@@ -1,6 +1,7 @@
-use crate::common::{Config, Error, Scene};
+use crate::common::{Config, Error, PaletteFile, PaletteFormat, Scene};
 use crate::components::Sprite;
-use crate::exporters::{Exporter, Plaintext};
+use crate::exporters::{Ansi, Exporter, Plaintext};
+use crate::resources::History;
 use libflate::gzip::{Decoder, Encoder};
 use std::fs::File;
 use std::path::{Path, PathBuf};
@@ -12,14 +13,26 @@ pub enum Loaded {
     // config is not needed to be loaded "generically"
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ExportFormat {
+    #[default]
     Txt,
+    Ansi, // colors and styles as escape sequences
 }
 
-impl Default for ExportFormat {
-    fn default() -> Self {
-        Self::Txt
+impl ExportFormat {
+    pub fn from_path(path: &str) -> Self {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some("ans") => ExportFormat::Ansi,
+            _ => ExportFormat::Txt,
+        }
+    }
+
+    fn ext(self) -> &'static str {
+        match self {
+            ExportFormat::Txt => "txt",
+            ExportFormat::Ansi => "ans",
+        }
     }
 }
 
@@ -27,12 +40,13 @@ impl Default for ExportFormat {
 //     "txt",
 // ];
 
-pub fn export_to_file(scene: Scene, format: ExportFormat, path: &str) -> Result<(), Error> {
-    let abs_path = to_abs_path_with_ext(path, "txt")?;
+pub fn export_to_file(scene: Scene, tick: usize, format: ExportFormat, path: &str) -> Result<(), Error> {
+    let abs_path = to_abs_path_with_ext(path, format.ext())?;
     let mut file = File::create(abs_path)?;
 
     match format {
-        ExportFormat::Txt => Plaintext::export(scene, &mut file)?,
+        ExportFormat::Txt => Plaintext::export(scene, tick, &mut file)?,
+        ExportFormat::Ansi => Ansi::export(scene, tick, &mut file)?,
     }
 
     Ok(())
@@ -51,6 +65,56 @@ pub fn scene_to_file(scene: &Scene, path: &str) -> Result<(), Error> {
     Ok(())
 }
 
+// crash recovery copy of a scene saved next to it as .<scene>.swp.rgz,
+// written to a temporary file first so a crash mid-write keeps the old swap
+pub fn scene_to_swap_file(scene: &Scene, path: &str) -> Result<(), Error> {
+    let swap_path = swap_path(path, "swp")?;
+    let tmp_path = swap_path_str(path, "swp.tmp")?;
+
+    scene_to_file(scene, &tmp_path)?;
+    std::fs::rename(&tmp_path, swap_path)?;
+
+    Ok(())
+}
+
+pub fn scene_from_swap_file(path: &str) -> Result<Scene, Error> {
+    match scene_from_rgz_file(&swap_path(path, "swp")?)? {
+        Loaded::Scene(scene) => Ok(scene),
+        Loaded::Sprite(_) => Err(Error::execution("Invalid swap file")),
+    }
+}
+
+pub fn swap_file_exists(path: &str) -> bool {
+    swap_path(path, "swp").map(|p| p.exists()).unwrap_or(false)
+}
+
+pub fn remove_swap_file(path: &str) -> Result<(), Error> {
+    match std::fs::remove_file(swap_path(path, "swp")?) {
+        Err(err) if err.kind() != std::io::ErrorKind::NotFound => Err(err.into()),
+        _ => Ok(()),
+    }
+}
+
+// undo history saved next to the scene as <scene>.rgz.undo
+pub fn history_to_file(history: &History, path: &str) -> Result<(), Error> {
+    let file = std::fs::File::create(history_path(path)?)?;
+    let mut encoder = Encoder::new(file)?;
+    let ronified = ron::ser::to_string(history)?;
+
+    use std::io::Write;
+    encoder.write_all(ronified.as_ref())?;
+    encoder.finish().into_result()?;
+
+    Ok(())
+}
+
+pub fn history_from_file(path: &str) -> Result<History, Error> {
+    let file = std::fs::File::open(history_path(path)?)?;
+    let decoder = Decoder::new(file)?;
+
+    Ok(ron::de::from_reader(decoder)?)
+}
+
 pub fn load_from_file(path: &str) -> Result<Loaded, Error> {
     let path = Path::new(path);
 
@@ -73,6 +137,26 @@ pub fn from_config_file(path: &Path) -> Result<Config, Error> {
     Ok(ron::de::from_reader(file)?)
 }
 
+pub fn palette_from_file(path: &str) -> Result<PaletteFile, Error> {
+    let abs_path = cwd_path(Path::new(path))?;
+    let source = std::fs::read_to_string(&abs_path)?;
+
+    PaletteFile::parse(&source, palette_format(&abs_path))
+}
+
+pub fn palette_to_file(palette: &PaletteFile, path: &str) -> Result<(), Error> {
+    let abs_path = cwd_path(Path::new(path))?;
+    let serialized = palette.serialize(palette_format(&abs_path))?;
+
+    std::fs::write(abs_path, serialized)?;
+
+    Ok(())
+}
+
+fn palette_format(path: &Path) -> PaletteFormat {
+    PaletteFormat::from_ext(path.extension().and_then(|ext| ext.to_str()).unwrap_or_default())
+}
+
 fn sprite_from_txt_file(path: &Path) -> Result<Sprite, Error> {
     let abs_path = cwd_path(path)?;
 
@@ -115,6 +199,25 @@ pub fn path_base(path: &str) -> String {
     String::default()
 }
 
+// hidden file next to the scene, .<name>.<kind>.rgz
+fn swap_path(path: &str, kind: &str) -> Result<PathBuf, std::io::Error> {
+    let abs_path = to_abs_path_with_ext(path, "rgz")?;
+    let name = abs_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+
+    Ok(abs_path.with_file_name(format!(".{}.{}.rgz", name, kind)))
+}
+
+fn swap_path_str(path: &str, kind: &str) -> Result<String, Error> {
+    swap_path(path, kind)?
+        .to_str()
+        .map(String::from)
+        .ok_or_else(|| Error::execution("Invalid swap file path"))
+}
+
+fn history_path(path: &str) -> Result<PathBuf, std::io::Error> {
+    Ok(to_abs_path_with_ext(path, "rgz")?.with_extension("rgz.undo"))
+}
+
 fn to_abs_path_with_ext(path: &str, ext: &str) -> Result<PathBuf, std::io::Error> {
     let raw_path = if Path::new(&path).extension() != Some(std::ffi::OsStr::new(ext)) {
         Path::new(&path).with_extension(ext)
@@ -0,0 +1,91 @@
+use crate::os::Terminal;
+use serde::{Deserialize, Serialize};
+use texel_types::Position2D;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GridChange {
+    Toggle,
+    Off,
+    Resize((u16, u16), Position2D), // cell size and offset
+    Color,                          // use selected bg color
+    ToggleSnap,
+}
+
+pub const GRID_WORDS: [&str; 2] = ["off", "color"];
+
+// tile grid overlay, positions snap to cell corners
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Grid {
+    pub size: (u16, u16), // cell width, height
+    pub offset: Position2D,
+    pub color: u8,
+    pub visible: bool,
+    pub snap: bool,
+}
+
+impl Default for Grid {
+    fn default() -> Self {
+        Grid {
+            size: (8, 4),
+            offset: Position2D::default(),
+            color: Terminal::grayscale_u8(3),
+            visible: false,
+            snap: false,
+        }
+    }
+}
+
+impl Grid {
+    pub fn apply(&mut self, change: GridChange, color: u8) {
+        match change {
+            GridChange::Toggle => self.visible = !self.visible,
+            GridChange::Off => self.visible = false,
+            GridChange::Resize(size, offset) => {
+                self.size = size;
+                self.offset = offset;
+                self.visible = true;
+            }
+            GridChange::Color => self.color = color,
+            GridChange::ToggleSnap => self.snap = !self.snap,
+        }
+    }
+
+    // is given absolute position on a grid line
+    pub fn on_line(&self, pos: Position2D) -> bool {
+        (pos.x - self.offset.x).rem_euclid(self.cell_w()) == 0 || (pos.y - self.offset.y).rem_euclid(self.cell_h()) == 0
+    }
+
+    // nearest cell corner
+    pub fn snap(&self, pos: Position2D) -> Position2D {
+        Position2D {
+            x: snap_axis(pos.x, self.offset.x, self.cell_w()),
+            y: snap_axis(pos.y, self.offset.y, self.cell_h()),
+        }
+    }
+
+    // splits texel distance into whole cells and the leftover
+    pub fn cells(&self, distance: Position2D) -> (Position2D, Position2D) {
+        let cells = Position2D {
+            x: distance.x / self.cell_w(),
+            y: distance.y / self.cell_h(),
+        };
+        let rest = Position2D {
+            x: distance.x % self.cell_w(),
+            y: distance.y % self.cell_h(),
+        };
+
+        (cells, rest)
+    }
+
+    pub fn cell_w(&self) -> i32 {
+        std::cmp::max(1, i32::from(self.size.0))
+    }
+
+    pub fn cell_h(&self) -> i32 {
+        std::cmp::max(1, i32::from(self.size.1))
+    }
+}
+
+fn snap_axis(value: i32, offset: i32, size: i32) -> i32 {
+    offset + (value - offset + size / 2).div_euclid(size) * size
+}
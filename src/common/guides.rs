@@ -0,0 +1,64 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuideChange {
+    Add(bool, Option<i32>), // "vertical" and world coordinate, cursor if none
+    Clear,
+}
+
+pub const GUIDE_WORDS: [&str; 3] = ["vertical", "horizontal", "clear"];
+
+// user placed guide lines in world coordinates
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Guides {
+    pub columns: Vec<i32>, // vertical guides
+    pub rows: Vec<i32>,    // horizontal guides
+}
+
+impl Guides {
+    pub fn is_empty(&self) -> bool {
+        self.columns.is_empty() && self.rows.is_empty()
+    }
+
+    pub fn add(&mut self, vertical: bool, at: i32) {
+        let lines = if vertical { &mut self.columns } else { &mut self.rows };
+
+        if !lines.contains(&at) {
+            lines.push(at);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.columns.clear();
+        self.rows.clear();
+    }
+
+    pub fn stop_x(&self, before: i32, after: i32, size: u16) -> i32 {
+        stop_at_lines(&self.columns, before, after, i32::from(size))
+    }
+
+    pub fn stop_y(&self, before: i32, after: i32, size: u16) -> i32 {
+        stop_at_lines(&self.rows, before, after, i32::from(size))
+    }
+}
+
+// stops movement at the first line crossed by the leading or trailing edge,
+// starting on a line doesn't stop so objects don't get stuck
+fn stop_at_lines(lines: &[i32], before: i32, after: i32, size: i32) -> i32 {
+    let delta = after - before;
+    let mut result = after;
+
+    for line in lines {
+        for edge in [0, std::cmp::max(0, size - 1)] {
+            let from = before + edge;
+            let to = after + edge;
+            let crossed = (delta > 0 && from < *line && *line <= to) || (delta < 0 && to <= *line && *line < from);
+
+            if crossed && (line - edge - before).abs() < (result - before).abs() {
+                result = line - edge;
+            }
+        }
+    }
+
+    result
+}
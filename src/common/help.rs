@@ -1,5 +1,5 @@
 use crate::common::fio::{scene_from_rgz_stream, Loaded};
-use texel_types::Scene;
+use crate::common::Scene;
 
 pub const HELP_TOPICS: [&str; 5] = ["overview", "commands", "modes", "keys", "config"];
 
@@ -1,21 +1,16 @@
-use crate::common::{ClipboardOp, Mode};
+use crate::common::{ClipboardOp, ExtStyle, MaskOp, Mode};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use texel_types::{ColorMode, Position2D, SymbolStyle, Which};
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum MoveMeta {
+    #[default]
     Relative,
     ToEdge,
     Alternative,
 }
 
-impl Default for MoveMeta {
-    fn default() -> Self {
-        Self::Relative
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum Event {
     #[default]
@@ -32,6 +27,7 @@ pub enum Event {
     Redo,
     Mode(Mode),
     ApplyStyle(SymbolStyle),
+    ApplyExtStyle(ExtStyle),
     SelectPalette(usize), // index of symbol/color/bookmark, 0x0-0xF as usize <0, 16)
     EditPalette(usize),   // index of symbol/color/bookmark, 0x0-0xF as usize <0, 16)
     ApplyColor(ColorMode),
@@ -47,6 +43,9 @@ pub enum Event {
     NewObject,
     Duplicate(usize), // count
     Deselect,
+    ToggleSymmetry,
+    MagicWand(MaskOp),
+    PalettePage(bool), // next page if true
     // "meta" keys
     Delete,
     Backspace,
@@ -60,6 +59,8 @@ pub enum Event {
     MouseDrag(Position2D),
     // terminal events
     Resize,
+    // timed events
+    Tick,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -95,6 +96,11 @@ impl ModesCharMap {
     pub fn fill_defaults(&mut self) {
         let def_map = CharMap::default();
 
+        // configs from before newer modes were added
+        while self.overrides.0.len() < Mode::count() {
+            self.overrides.0.push(CharMap::new());
+        }
+
         for (k, e) in def_map.0 {
             // if we don't have this event mapped to a key yet
             if !self.all_modes.0.values().any(|v| v == &e) {
@@ -162,6 +168,11 @@ impl Default for CharMap {
         map.insert('q', Event::ApplyStyle(SymbolStyle::Bold));
         map.insert('Q', Event::ApplyStyle(SymbolStyle::Italic));
         map.insert('w', Event::ApplyStyle(SymbolStyle::Underline));
+        map.insert('W', Event::ApplyExtStyle(ExtStyle::DoubleUnderline));
+        map.insert('o', Event::ApplyExtStyle(ExtStyle::Dim));
+        map.insert('r', Event::ApplyExtStyle(ExtStyle::Reverse));
+        map.insert('O', Event::ApplyExtStyle(ExtStyle::Blink));
+        map.insert('S', Event::ApplyExtStyle(ExtStyle::Strikethrough));
 
         map.insert('h', Event::Left(MoveMeta::Relative));
         map.insert('j', Event::Down(MoveMeta::Relative));
@@ -196,6 +207,11 @@ impl Default for CharMap {
         map.insert('\n', Event::Confirm);
         map.insert('\t', Event::SelectObject(Which::Next, false));
         map.insert('v', Event::SelectRegion);
+        map.insert('M', Event::ToggleSymmetry);
+        map.insert('g', Event::MagicWand(MaskOp::Replace));
+        map.insert('G', Event::MagicWand(MaskOp::Add));
+        map.insert('>', Event::PalettePage(true));
+        map.insert('<', Event::PalettePage(false));
 
         CharMap(map)
     }
@@ -9,18 +9,13 @@ pub enum OnQuit {
     Save,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum SelectMode {
+    #[default]
     Object,
     Region,
 }
 
-impl Default for SelectMode {
-    fn default() -> Self {
-        Self::Object
-    }
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum Mode {
     Object(SelectMode),
@@ -32,6 +27,8 @@ pub enum Mode {
     Command,
     Quitting(OnQuit), // true for force quit
     Help(usize),      // help index
+    Play,             // animation playback
+    History(usize),   // undo tree view, selected state id
 }
 
 impl Default for Mode {
@@ -42,7 +39,7 @@ impl Default for Mode {
 
 impl Mode {
     pub const fn count() -> usize {
-        9
+        11
     }
 
     pub fn modifies_cursor(&self) -> bool {
@@ -68,6 +65,8 @@ impl Mode {
             Mode::Command => 6,
             Mode::Help(_) => 7,
             Mode::Quitting(_) => 8,
+            Mode::Play => 9,
+            Mode::History(_) => 10,
         }
     }
 
@@ -85,6 +84,8 @@ impl Mode {
             Mode::Command => "COMMAND",
             Mode::Quitting(_) => "QUITTING",
             Mode::Help(_) => "HELP",
+            Mode::Play => "PLAY",
+            Mode::History(_) => "UNDOLIST",
         }
     }
 }
@@ -0,0 +1,51 @@
+use crate::os::Terminal;
+
+pub const ONION_WORDS: [&str; 4] = ["off", "previous", "next", "both"];
+
+// dimmed neighbouring frames shown under the edited one
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OnionSkin {
+    pub previous: bool,
+    pub next: bool,
+    pub depth: usize, // how many frames to show in each direction
+}
+
+impl Default for OnionSkin {
+    fn default() -> Self {
+        OnionSkin {
+            previous: true,
+            next: false,
+            depth: 1,
+        }
+    }
+}
+
+impl OnionSkin {
+    // frame indexes to render for given current index, farthest first
+    pub fn frames(&self, index: usize, frame_count: usize) -> Vec<(usize, usize, bool)> {
+        let mut result = Vec::new();
+
+        for distance in (1..=self.depth).rev() {
+            if self.previous && distance <= index {
+                result.push((index - distance, distance, false));
+            }
+
+            if self.next && index + distance < frame_count {
+                result.push((index + distance, distance, true));
+            }
+        }
+
+        result
+    }
+
+    // previous frames are tinted red, next ones blue, fading with distance
+    pub fn color(&self, distance: usize, next: bool) -> u8 {
+        let level = if distance > 1 { 2 } else { 3 };
+
+        if next {
+            Terminal::rgb_u8(0, 0, level)
+        } else {
+            Terminal::rgb_u8(level, 0, 0)
+        }
+    }
+}
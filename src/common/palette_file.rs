@@ -0,0 +1,117 @@
+use crate::common::{Error, Rgb};
+use crate::resources::SymbolPalette;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PaletteOp {
+    Load(String),
+    Save(String),
+}
+
+pub const PALETTE_WORDS: [&str; 2] = ["load", "save"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaletteFormat {
+    Gpl, // GIMP palette
+    Hex, // one #rrggbb per line
+    Ron, // texel native, includes symbols
+}
+
+impl PaletteFormat {
+    pub fn from_ext(ext: &str) -> Self {
+        match ext {
+            "gpl" => PaletteFormat::Gpl,
+            "ron" => PaletteFormat::Ron,
+            _ => PaletteFormat::Hex,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaletteFile {
+    pub colors: Vec<Rgb>,
+    #[serde(default)]
+    pub symbols: Option<SymbolPalette>,
+}
+
+impl PaletteFile {
+    pub fn parse(source: &str, format: PaletteFormat) -> Result<Self, Error> {
+        let result = match format {
+            PaletteFormat::Gpl => parse_gpl(source)?,
+            PaletteFormat::Hex => parse_hex(source)?,
+            PaletteFormat::Ron => ron::de::from_str(source)?,
+        };
+
+        if result.colors.is_empty() {
+            return Err(Error::execution("No colors in palette file"));
+        }
+
+        Ok(result)
+    }
+
+    pub fn serialize(&self, format: PaletteFormat) -> Result<String, Error> {
+        let result = match format {
+            PaletteFormat::Gpl => {
+                let mut lines = vec![
+                    String::from("GIMP Palette"),
+                    String::from("Name: texel"),
+                    String::from("#"),
+                ];
+
+                for rgb in self.colors.iter() {
+                    lines.push(format!("{:3} {:3} {:3}\t{}", rgb.r, rgb.g, rgb.b, rgb));
+                }
+
+                lines.join("\n") + "\n"
+            }
+            PaletteFormat::Hex => {
+                let lines: Vec<String> = self.colors.iter().map(|rgb| rgb.to_string()).collect();
+
+                lines.join("\n") + "\n"
+            }
+            PaletteFormat::Ron => ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default())?,
+        };
+
+        Ok(result)
+    }
+}
+
+// "GIMP Palette" header, then "r g b [name]" lines, comments start with #
+fn parse_gpl(source: &str) -> Result<PaletteFile, Error> {
+    let mut lines = source.lines();
+
+    if lines.next().map(|l| l.trim()) != Some("GIMP Palette") {
+        return Err(Error::execution("Invalid GIMP palette header"));
+    }
+
+    let mut colors = Vec::new();
+    for line in lines.map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with('#') || line.contains(':') {
+            continue; // comments, Name: and Columns:
+        }
+
+        let mut channels = line.split_ascii_whitespace().map(|c| c.parse::<u8>());
+        match (channels.next(), channels.next(), channels.next()) {
+            (Some(Ok(r)), Some(Ok(g)), Some(Ok(b))) => colors.push(Rgb { r, g, b }),
+            _ => return Err(Error::execution("Invalid GIMP palette color")),
+        }
+    }
+
+    Ok(PaletteFile { colors, symbols: None })
+}
+
+// one hex color per line, blank lines and ; or // comments are skipped
+fn parse_hex(source: &str) -> Result<PaletteFile, Error> {
+    let mut colors = Vec::new();
+
+    for line in source.lines().map(|l| l.trim()) {
+        if line.is_empty() || line.starts_with(';') || line.starts_with("//") {
+            continue;
+        }
+
+        let hex = line.split_ascii_whitespace().next().unwrap_or_default();
+        colors.push(Rgb::parse(hex)?);
+    }
+
+    Ok(PaletteFile { colors, symbols: None })
+}
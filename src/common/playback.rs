@@ -0,0 +1,120 @@
+use crate::common::{Animation, LoopMode};
+use std::time::Duration;
+
+pub const PLAYBACK_WORDS: [&str; 4] = ["loop", "pingpong", "all", "selected"];
+
+// animation playback settings, elapsed counts ms played since start
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Playback {
+    pub fps: u16,
+    pub ping_pong: bool, // for sprites and timeline without own loop mode
+    pub all: bool,       // animate all sprites instead of selected only
+    pub elapsed: u64,
+    pub wait: u64,     // ms until next frame change of any played sprite
+    pub start: usize,  // timeline tick playback started at
+    pub length: usize, // last keyframe tick in scene
+}
+
+impl Default for Playback {
+    fn default() -> Self {
+        Playback {
+            fps: 8,
+            ping_pong: false,
+            all: false,
+            elapsed: 0,
+            wait: 125,
+            start: 0,
+            length: 0,
+        }
+    }
+}
+
+impl Playback {
+    pub fn interval(&self) -> Duration {
+        Duration::from_millis(self.wait)
+    }
+
+    // default frame duration when sprite frame has none
+    pub fn frame_ms(&self) -> u64 {
+        1000 / u64::from(std::cmp::max(1, self.fps))
+    }
+
+    // frame index to show for sprite with given frame count and ms until it changes
+    pub fn frame(&self, frame_count: usize, animation: Option<&Animation>) -> (usize, Option<u64>) {
+        if frame_count < 2 {
+            return (0, None);
+        }
+
+        let loop_mode = match animation {
+            Some(animation) => animation.loop_mode,
+            None if self.ping_pong => LoopMode::PingPong,
+            None => LoopMode::Loop,
+        };
+        let duration = |index: usize| {
+            animation
+                .and_then(|a| a.duration(index))
+                .map_or(self.frame_ms(), u64::from)
+                .max(1)
+        };
+        let back = if loop_mode == LoopMode::PingPong {
+            frame_count - 2
+        } else {
+            0
+        };
+        let steps: Vec<usize> = (0..frame_count).chain((1..=back).rev()).collect();
+        let period: u64 = steps.iter().map(|i| duration(*i)).sum();
+
+        let mut time = match loop_mode {
+            LoopMode::Once if self.elapsed >= period => return (frame_count - 1, None),
+            LoopMode::Once => self.elapsed,
+            _ => self.elapsed % period,
+        };
+
+        for index in steps {
+            let duration = duration(index);
+
+            if time < duration {
+                return (index, Some(duration - time));
+            }
+
+            time -= duration;
+        }
+
+        (0, None)
+    }
+
+    // timeline tick to show, steps at playback speed
+    pub fn timeline_tick(&self) -> usize {
+        let tick = self.start + (self.elapsed / self.frame_ms()) as usize;
+
+        self.step(tick, self.length + 1)
+    }
+
+    // ms until timeline tick changes
+    pub fn timeline_wait(&self) -> Option<u64> {
+        if self.length == 0 {
+            return None;
+        }
+
+        Some(self.frame_ms() - self.elapsed % self.frame_ms())
+    }
+
+    fn step(&self, tick: usize, count: usize) -> usize {
+        if count < 2 {
+            return 0;
+        }
+
+        if self.ping_pong {
+            let period = (count - 1) * 2;
+            let step = tick % period;
+
+            if step < count {
+                step
+            } else {
+                period - step
+            }
+        } else {
+            tick % count
+        }
+    }
+}
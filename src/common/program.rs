@@ -2,7 +2,7 @@ use legion::*;
 use std::io::stdout;
 use std::path::Path;
 
-use crate::common::{fio, Action, Config, ConfigV2, Event, InputEvent};
+use crate::common::{fio, Action, Config, ConfigV2, Error, Event, InputEvent, Mode, Remaps};
 use crate::os::{InputSource, Terminal};
 use crate::resources::{CmdLine, ColorPalette, FrameBuffer, State, SymbolPalette};
 use crate::systems::*;
@@ -20,6 +20,11 @@ pub fn run(args: Vec<String>) {
 
     let mut out = FrameBuffer::new(usize::from(ts.0), usize::from(ts.1));
     let mut state = State::default();
+    if let Some(budget) = config.history_budget {
+        state.set_history_budget(budget);
+    }
+    state.persistent_undo = config.persistent_undo;
+    state.autosave = config.autosave;
     let mut resources = build_resources(&config);
     let input_source = InputSource::from(config.char_map.clone());
 
@@ -33,14 +38,28 @@ pub fn run(args: Vec<String>) {
     TexelSystems::run(&mut world, &mut state, &mut resources, &mut out);
 
     // flush buffers to terminal
+    out.set_colors(&state.colors);
     out.flush_into(terminal.endpoint()).unwrap();
 
     loop {
-        let mapped = input_source.next_event(state.mode());
+        let mapped = if state.mode() == Mode::Play {
+            // playback needs timed ticks, don't block on input
+            input_source
+                .poll_event(state.mode(), state.playback.interval())
+                .unwrap_or((Event::Tick, None))
+        } else if let Some(left) = state.swap_due_in() {
+            // wake up for autosave when idle
+            input_source
+                .poll_event(state.mode(), left)
+                .unwrap_or((Event::None, None))
+        } else {
+            input_source.next_event(state.mode())
+        };
         // handle input
         dispatch_input_event(mapped, &mut state, &mut out, &mut terminal);
         TexelSystems::run(&mut world, &mut state, &mut resources, &mut out);
         // flush buffers to terminal
+        out.set_colors(&state.colors);
         out.flush_into(terminal.endpoint()).unwrap();
 
         if state.quitting() {
@@ -50,6 +69,13 @@ pub fn run(args: Vec<String>) {
     // reset tty back with clear screen
     terminal.restore();
 
+    // clean exit, nothing to recover unless an earlier session's swap was left alone
+    if let Some(path) = state.save_file(&None).ok().filter(|_| !state.stale_swap()) {
+        if let Err(err) = fio::remove_swap_file(&path) {
+            eprintln!("Unable to remove swap file: {}", err);
+        }
+    }
+
     // save config
     save_config(config, &config_file, &resources);
 }
@@ -59,6 +85,7 @@ fn build_resources(config: &ConfigV2) -> Resources {
     resources.insert(CmdLine::default());
     resources.insert(config.color_palette.clone());
     resources.insert(config.symbol_palette.clone());
+    resources.insert(config.remaps.clone());
 
     resources
 }
@@ -80,6 +107,14 @@ fn load_from(args: Vec<String>, state: &mut State) -> bool {
             }
         }
 
+        // previous session didn't exit cleanly
+        if args.len() == 2 && fio::swap_file_exists(&args[1]) {
+            state.set_stale_swap(true);
+            state.set_error(Error::execution(
+                "Swap file found, autosave is off until :recover or :recover discard",
+            ));
+        }
+
         true
     } else {
         false
@@ -107,9 +142,11 @@ fn check_terminal_size(ts: (u16, u16)) {
 fn save_config(mut v2: ConfigV2, config_file: &Path, resources: &Resources) {
     let cp = resources.get::<ColorPalette>().unwrap();
     let sp = resources.get::<SymbolPalette>().unwrap();
+    let remaps = resources.get::<Remaps>().unwrap();
 
     v2.color_palette = cp.clone();
     v2.symbol_palette = sp.clone();
+    v2.remaps = remaps.clone();
 
     let config = Config::from(v2);
 
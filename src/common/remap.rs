@@ -0,0 +1,89 @@
+use crate::common::{ColorTable, Error, Rgb};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use texel_types::ColorMode;
+
+pub const REMAP_WORDS: [&str; 5] = ["save", "delete", "fg", "bg", "all"];
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemapOp {
+    Apply(RemapSource, Option<ColorMode>, bool), // colors to change (both if none) and "all frames"
+    Save(String, ColorRemap),
+    Delete(String),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RemapSource {
+    Named(String),
+    Inline(ColorRemap),
+}
+
+// ANSI index or exact color going through the scene color table
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RemapColor {
+    Index(u8),
+    Rgb(Rgb),
+}
+
+impl RemapColor {
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        if source.starts_with('#') {
+            Ok(RemapColor::Rgb(Rgb::parse(source)?))
+        } else {
+            source
+                .parse::<u8>()
+                .map(RemapColor::Index)
+                .map_err(|_| Error::InvalidParam("Invalid remap color, use 0..255 or #rrggbb"))
+        }
+    }
+}
+
+// source to target color pairs, applied all at once so swaps work
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ColorRemap(pub Vec<(RemapColor, RemapColor)>);
+
+impl ColorRemap {
+    // "from=to" pairs separated by commas, e.g. 196=21,#ffffff=16
+    pub fn parse(source: &str) -> Result<Self, Error> {
+        let mut pairs = Vec::new();
+
+        for pair in source.split(',').filter(|p| !p.is_empty()) {
+            let mut colors = pair.split('=');
+
+            match (colors.next(), colors.next(), colors.next()) {
+                (Some(from), Some(to), None) => pairs.push((RemapColor::parse(from)?, RemapColor::parse(to)?)),
+                _ => return Err(Error::InvalidParam("Invalid remap pair, use from=to")),
+            }
+        }
+
+        if pairs.is_empty() {
+            return Err(Error::InvalidParam("Empty remap"));
+        }
+
+        Ok(ColorRemap(pairs))
+    }
+
+    // index to index mapping for the scene, assigns exact target colors as needed
+    pub fn resolve(&self, colors: &mut ColorTable, used: &BTreeSet<u8>) -> Result<BTreeMap<u8, u8>, Error> {
+        let mut result = BTreeMap::new();
+
+        for (from, to) in self.0.iter() {
+            let from = match from {
+                RemapColor::Index(index) => *index,
+                RemapColor::Rgb(rgb) => colors.index_of(*rgb).unwrap_or_else(|| rgb.nearest_ansi()),
+            };
+            let to = match to {
+                RemapColor::Index(index) => *index,
+                RemapColor::Rgb(rgb) => colors.assign(*rgb, used)?,
+            };
+
+            result.insert(from, to);
+        }
+
+        Ok(result)
+    }
+}
+
+// named remaps kept in config
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Remaps(pub BTreeMap<String, ColorRemap>);
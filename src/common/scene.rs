@@ -1,33 +1,199 @@
-use crate::components::Bookmark;
+use crate::common::{Animation, ColorTable, ExtStyleMap, Grid, Guides, Keyframes};
+use crate::components::{Bookmark, Group, GroupInfo, Hidden, Locked};
 use legion::*;
+use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-pub use texel_types::{Position, Position2D, Scene, SceneV2, Sprite};
+pub use texel_types::{Position, Position2D, SceneV1, SceneV2, Sprite};
+
+// texel_types::Scene wrapper, adds texel specific scene data on top.
+// V1 and V2 are kept as-is so older files deserialize the same way.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Scene {
+    V1(SceneV1),
+    V2(SceneV2),
+    V3(SceneV3),
+}
+
+impl Default for Scene {
+    fn default() -> Self {
+        Scene::V3(SceneV3::default())
+    }
+}
+
+impl Scene {
+    pub fn current(self) -> SceneV3 {
+        match self {
+            Self::V1(scene) => SceneV3::from(SceneV2::from(scene)),
+            Self::V2(scene) => SceneV3::from(scene),
+            Self::V3(scene) => scene,
+        }
+    }
+
+    pub fn grid(&self) -> Grid {
+        match self {
+            Self::V3(scene) => scene.grid,
+            _ => Grid::default(),
+        }
+    }
+
+    pub fn guides(&self) -> Guides {
+        match self {
+            Self::V3(scene) => scene.guides.clone(),
+            _ => Guides::default(),
+        }
+    }
+
+    pub fn colors(&self) -> ColorTable {
+        match self {
+            Self::V3(scene) => scene.colors.clone(),
+            _ => ColorTable::default(),
+        }
+    }
+}
+
+// per object data not present in texel_types
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ObjectInfo {
+    #[serde(default)]
+    pub group: Option<usize>, // innermost group the object belongs to
+    #[serde(default)]
+    pub hidden: bool,
+    #[serde(default)]
+    pub locked: bool,
+    #[serde(default)]
+    pub animation: Option<Animation>,
+    #[serde(default)]
+    pub keyframes: Keyframes, // timeline position keys
+    #[serde(default)]
+    pub ext_styles: ExtStyleMap,
+}
+
+impl ObjectInfo {
+    pub fn in_group(group: Option<usize>) -> Self {
+        ObjectInfo {
+            group,
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SceneV3 {
+    pub objects: Vec<(Sprite, Position, ObjectInfo)>,
+    pub bookmarks: BTreeMap<usize, Position2D>,
+    #[serde(default)]
+    pub groups: BTreeMap<usize, Option<usize>>, // group id -> parent group id
+    #[serde(default)]
+    pub grid: Grid,
+    #[serde(default)]
+    pub guides: Guides,
+    #[serde(default)]
+    pub colors: ColorTable, // exact RGB values of color indexes
+}
+
+impl SceneV3 {
+    // keyed objects moved to their interpolated positions at given timeline tick
+    pub fn at_tick(mut self, tick: usize) -> Self {
+        for (_, pos, info) in self.objects.iter_mut() {
+            if let Some(at) = info.keyframes.at(tick) {
+                *pos = at;
+            }
+        }
+
+        self
+    }
+}
+
+impl From<SceneV2> for SceneV3 {
+    fn from(older: SceneV2) -> Self {
+        SceneV3 {
+            objects: older
+                .objects
+                .into_iter()
+                .map(|(sprite, pos)| (sprite, pos, ObjectInfo::default()))
+                .collect(),
+            bookmarks: older.bookmarks,
+            groups: BTreeMap::new(),
+            grid: Grid::default(),
+            guides: Guides::default(),
+            colors: ColorTable::default(),
+        }
+    }
+}
 
 pub trait SceneExt {
     fn from_world(world: &mut World) -> Scene;
 
-    fn from_objects(objects: Vec<(Sprite, Position)>, bookmarks: BTreeMap<usize, Position2D>) -> Scene;
+    fn from_objects(
+        objects: Vec<(Sprite, Position, ObjectInfo)>,
+        bookmarks: BTreeMap<usize, Position2D>,
+        groups: BTreeMap<usize, Option<usize>>,
+    ) -> Scene;
 }
 
 impl SceneExt for Scene {
     fn from_world(world: &mut World) -> Scene {
         let mut objects = Vec::new();
-        let mut bookmarks = BTreeMap::new();
 
-        let mut query = <(Read<Sprite>, Read<Position>)>::query();
-        for (sprite, pos) in query.iter(world) {
-            objects.push((sprite.clone(), *pos));
+        let mut query = <(
+            Read<Sprite>,
+            Read<Position>,
+            TryRead<Group>,
+            TryRead<Hidden>,
+            TryRead<Locked>,
+            TryRead<Animation>,
+            TryRead<Keyframes>,
+            TryRead<ExtStyleMap>,
+        )>::query();
+        for (sprite, pos, group, hidden, locked, animation, keyframes, ext_styles) in query.iter(world) {
+            let info = ObjectInfo {
+                group: group.map(|g| g.0),
+                hidden: hidden.is_some(),
+                locked: locked.is_some(),
+                animation: animation.cloned(),
+                keyframes: keyframes.cloned().unwrap_or_default(),
+                ext_styles: ext_styles.cloned().unwrap_or_default(),
+            };
+            objects.push((sprite.clone(), *pos, info));
         }
 
-        let mut query = <(Read<Bookmark>, Read<Position2D>)>::query();
-        for (bookmark, pos) in query.iter(world) {
-            bookmarks.insert(bookmark.0, *pos);
-        }
+        Scene::from_objects(objects, world_bookmarks(world), world_groups(world))
+    }
 
-        Scene::V2(SceneV2 { objects, bookmarks })
+    fn from_objects(
+        objects: Vec<(Sprite, Position, ObjectInfo)>,
+        bookmarks: BTreeMap<usize, Position2D>,
+        groups: BTreeMap<usize, Option<usize>>,
+    ) -> Scene {
+        Scene::V3(SceneV3 {
+            objects,
+            bookmarks,
+            groups,
+            grid: Grid::default(),
+            guides: Guides::default(),
+            colors: ColorTable::default(),
+        })
     }
+}
+
+pub fn world_bookmarks(world: &mut World) -> BTreeMap<usize, Position2D> {
+    let mut bookmarks = BTreeMap::new();
 
-    fn from_objects(objects: Vec<(Sprite, Position)>, bookmarks: BTreeMap<usize, Position2D>) -> Scene {
-        Scene::V2(SceneV2 { objects, bookmarks })
+    let mut query = <(Read<Bookmark>, Read<Position2D>)>::query();
+    for (bookmark, pos) in query.iter(world) {
+        bookmarks.insert(bookmark.0, *pos);
     }
+
+    bookmarks
+}
+
+pub fn world_groups(world: &mut World) -> BTreeMap<usize, Option<usize>> {
+    let mut groups = BTreeMap::new();
+
+    let mut query = <Read<GroupInfo>>::query();
+    for info in query.iter(world) {
+        groups.insert(info.id, info.parent);
+    }
+
+    groups
 }
@@ -1,8 +1,27 @@
+use crate::common::Anchor;
+use crate::resources::ColorPalette;
 pub use texel_types::{Bounds, Sprite, DEFAULT_BG_U8};
+use texel_types::{ColorMode, Dimension, Position2D, SymbolStyles, Texel, Texels, DEFAULT_FG_U8};
 
 // extra stuff for texel only
 pub trait SpriteExt {
     fn clear_blank_texels(&mut self, area: Option<Bounds>) -> bool;
+
+    fn apply_color_at(&mut self, cm: ColorMode, color: u8, pos: Position2D) -> Bounds;
+
+    fn outline(&mut self, symbol: char, color: u8) -> Bounds;
+
+    fn drop_shadow(&mut self, offset: Position2D) -> Bounds;
+
+    fn shadow_sprite(&self) -> Sprite;
+
+    fn trim(&mut self) -> Bounds;
+
+    fn resize_canvas(&mut self, dim: Dimension, anchor: Anchor) -> Bounds;
+
+    fn insert_frame(&mut self, index: usize, texels: Texels) -> Bounds;
+
+    fn swap_frames(&mut self, a: usize, b: usize) -> bool;
 }
 
 impl SpriteExt for Sprite {
@@ -10,7 +29,7 @@ impl SpriteExt for Sprite {
         let count = self.frames[self.index].len();
         // remove "empty" (char ' ') texels with default BG in given area
         self.frames[self.index].retain(|t| {
-            let mut r = t.symbol != ' ' || t.bg != DEFAULT_BG_U8;
+            let mut r = !is_blank(t);
             if let Some(bounds) = area {
                 r |= !bounds.contains(t.pos);
             }
@@ -20,4 +39,193 @@ impl SpriteExt for Sprite {
 
         self.frames[self.index].len() != count
     }
+
+    // same as apply_color for a single position but returns the new bounds
+    // so callers can follow the sprite shift when colors are applied outside it
+    fn apply_color_at(&mut self, cm: ColorMode, color: u8, pos: Position2D) -> Bounds {
+        let mut texel = match self.read_texel(pos) {
+            Some(existing) => existing.clone(),
+            None => Texel {
+                pos,
+                symbol: ' ',
+                bg: DEFAULT_BG_U8,
+                fg: DEFAULT_FG_U8,
+                styles: SymbolStyles::new(),
+            },
+        };
+
+        match cm {
+            ColorMode::Bg => texel.bg = color,
+            ColorMode::Fg => texel.fg = color,
+        }
+
+        self.apply_texels(vec![texel], Position2D::default())
+    }
+
+    // surrounds non-blank texels in all frames with a 1 texel wide outline
+    fn outline(&mut self, symbol: char, color: u8) -> Bounds {
+        for frame in self.frames.iter_mut() {
+            let mut outline: Texels = Vec::new();
+
+            for texel in frame.iter().filter(|t| !is_blank(t)) {
+                for y in -1..=1 {
+                    for x in -1..=1 {
+                        let pos = texel.pos + Position2D { x, y };
+
+                        if is_free(frame, pos) && !outline.iter().any(|t| t.pos == pos) {
+                            outline.push(Texel {
+                                pos,
+                                symbol,
+                                bg: DEFAULT_BG_U8,
+                                fg: color,
+                                styles: SymbolStyles::new(),
+                            });
+                        }
+                    }
+                }
+            }
+
+            merge_into(frame, outline);
+        }
+
+        recalculate_bounds(self)
+    }
+
+    // adds darker copies of non-blank texels moved by offset under existing ones in all frames
+    fn drop_shadow(&mut self, offset: Position2D) -> Bounds {
+        for frame in self.frames.iter_mut() {
+            let shadow: Texels = shadow_texels(frame)
+                .into_iter()
+                .map(|mut t| {
+                    t.pos += offset;
+                    t
+                })
+                .filter(|t| is_free(frame, t.pos))
+                .collect();
+
+            merge_into(frame, shadow);
+        }
+
+        recalculate_bounds(self)
+    }
+
+    // separate sprite with darker copies of non-blank texels for all frames,
+    // a new object so id and labels stay with the source
+    fn shadow_sprite(&self) -> Sprite {
+        Sprite {
+            frames: self.frames.iter().map(|frame| shadow_texels(frame)).collect(),
+            index: self.index,
+            ..Sprite::default()
+        }
+    }
+
+    // removes blank texels outside of the non-blank area in all frames
+    fn trim(&mut self) -> Bounds {
+        let non_blank: Vec<Position2D> = self.all_iter().filter(|t| !is_blank(t)).map(|t| t.pos).collect();
+        let first = match non_blank.first() {
+            Some(pos) => *pos,
+            None => return recalculate_bounds(self), // nothing to keep, leave as is
+        };
+
+        let mut top_left = first;
+        let mut bottom_right = first;
+        for pos in non_blank.iter() {
+            top_left.x = std::cmp::min(top_left.x, pos.x);
+            top_left.y = std::cmp::min(top_left.y, pos.y);
+            bottom_right.x = std::cmp::max(bottom_right.x, pos.x);
+            bottom_right.y = std::cmp::max(bottom_right.y, pos.y);
+        }
+        let area = top_left.area(bottom_right);
+
+        for frame in self.frames.iter_mut() {
+            frame.retain(|t| area.contains(t.pos));
+        }
+
+        recalculate_bounds(self)
+    }
+
+    // crops or pads all frames to given dimension, padding is done using blank texels
+    fn resize_canvas(&mut self, dim: Dimension, anchor: Anchor) -> Bounds {
+        let current = Dimension::for_sprite(self);
+        let area = Bounds::Free(anchor.offset(current, dim), dim);
+
+        for frame in self.frames.iter_mut() {
+            frame.retain(|t| area.contains(t.pos));
+
+            for pos in area.into_iter() {
+                if !frame.iter().any(|t| t.pos == pos) {
+                    frame.push(Texel {
+                        pos,
+                        symbol: ' ',
+                        bg: DEFAULT_BG_U8,
+                        fg: DEFAULT_FG_U8,
+                        styles: SymbolStyles::new(),
+                    });
+                }
+            }
+        }
+
+        recalculate_bounds(self)
+    }
+
+    // inserts given frame and makes it current, bounds can grow with it
+    fn insert_frame(&mut self, index: usize, texels: Texels) -> Bounds {
+        let index = std::cmp::min(index, self.frames.len());
+
+        self.frames.insert(index, texels);
+        self.index = index;
+
+        recalculate_bounds(self)
+    }
+
+    // swaps two frames, current frame index follows its frame
+    fn swap_frames(&mut self, a: usize, b: usize) -> bool {
+        if a == b || a >= self.frames.len() || b >= self.frames.len() {
+            return false;
+        }
+
+        self.frames.swap(a, b);
+        if self.index == a {
+            self.index = b;
+        } else if self.index == b {
+            self.index = a;
+        }
+
+        true
+    }
+}
+
+fn is_blank(texel: &Texel) -> bool {
+    texel.symbol == ' ' && texel.bg == DEFAULT_BG_U8
+}
+
+// true if there's no texel or just a blank one on given position
+fn is_free(frame: &[Texel], pos: Position2D) -> bool {
+    !frame.iter().any(|t| t.pos == pos && !is_blank(t))
+}
+
+fn shadow_texels(frame: &[Texel]) -> Texels {
+    frame
+        .iter()
+        .filter(|t| !is_blank(t))
+        .map(|t| Texel {
+            pos: t.pos,
+            symbol: t.symbol,
+            bg: ColorPalette::darken(t.bg),
+            fg: ColorPalette::darken(t.fg),
+            styles: t.styles,
+        })
+        .collect()
+}
+
+// replaces blank texels on the same positions
+fn merge_into(frame: &mut Texels, texels: Texels) {
+    frame.retain(|t| !texels.iter().any(|n| n.pos == t.pos));
+    frame.extend(texels);
+}
+
+// texels can get negative positions on any frame, applying an empty texel
+// list re-calculates the bounds for all frames and shifts texels as needed
+fn recalculate_bounds(sprite: &mut Sprite) -> Bounds {
+    sprite.apply_texels(Texels::new(), Position2D::default())
 }
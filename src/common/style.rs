@@ -0,0 +1,103 @@
+use crate::components::{Position2D, Sprite};
+use big_enum_set::{BigEnumSet, BigEnumSetType};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+// styles past the bold, italic and underline texels carry on their own
+#[derive(Debug, BigEnumSetType, Serialize, Deserialize)]
+pub enum ExtStyle {
+    Dim,
+    Reverse,
+    Blink,
+    Strikethrough,
+    DoubleUnderline,
+}
+
+pub type ExtStyles = BigEnumSet<ExtStyle>;
+
+// per object extended styles keyed by frame index and sprite relative texel
+// position, texel_types has no room for them in the texel itself
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtStyleMap(pub BTreeMap<(usize, i32, i32), ExtStyles>);
+
+impl ExtStyleMap {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn get(&self, frame: usize, pos: Position2D) -> ExtStyles {
+        self.0.get(&(frame, pos.x, pos.y)).copied().unwrap_or_default()
+    }
+
+    pub fn toggle(&mut self, frame: usize, pos: Position2D, style: ExtStyle) {
+        let mut styles = self.get(frame, pos);
+
+        if styles.contains(style) {
+            styles.remove(style);
+        } else {
+            styles.insert(style);
+        }
+
+        if styles.is_empty() {
+            self.0.remove(&(frame, pos.x, pos.y));
+        } else {
+            self.0.insert((frame, pos.x, pos.y), styles);
+        }
+    }
+
+    // sprite origin moved to top_left, texels in all frames shifted back by it
+    pub fn origin_moved(&mut self, top_left: Position2D) {
+        if top_left == Position2D::default() {
+            return;
+        }
+
+        self.remap(|(frame, x, y)| vec![(frame, x - top_left.x, y - top_left.y)]);
+    }
+
+    // keeps frame indexes aligned with frames, new frame copies the one at index
+    pub fn frame_inserted(&mut self, index: usize) {
+        self.remap(|(frame, x, y)| match frame {
+            f if f < index => vec![(f, x, y)],
+            f if f == index => vec![(f, x, y), (f + 1, x, y)],
+            f => vec![(f + 1, x, y)],
+        });
+    }
+
+    pub fn blank_frame_inserted(&mut self, index: usize) {
+        self.remap(|(frame, x, y)| match frame {
+            f if f < index => vec![(f, x, y)],
+            f => vec![(f + 1, x, y)],
+        });
+    }
+
+    pub fn frames_swapped(&mut self, a: usize, b: usize) {
+        self.remap(|(frame, x, y)| match frame {
+            f if f == a => vec![(b, x, y)],
+            f if f == b => vec![(a, x, y)],
+            f => vec![(f, x, y)],
+        });
+    }
+
+    pub fn frame_removed(&mut self, index: usize) {
+        self.remap(|(frame, x, y)| match frame {
+            f if f < index => vec![(f, x, y)],
+            f if f == index => Vec::new(),
+            f => vec![(f - 1, x, y)],
+        });
+    }
+
+    // drops styles of positions left without a texel
+    pub fn retain_texels(&mut self, sprite: &Sprite) {
+        self.0.retain(|(frame, x, y), _| match sprite.frames.get(*frame) {
+            Some(texels) => texels.iter().any(|t| t.pos.x == *x && t.pos.y == *y),
+            None => false,
+        });
+    }
+
+    fn remap(&mut self, keys: impl Fn((usize, i32, i32)) -> Vec<(usize, i32, i32)>) {
+        self.0 = std::mem::take(&mut self.0)
+            .into_iter()
+            .flat_map(|(key, styles)| keys(key).into_iter().map(move |key| (key, styles)))
+            .collect();
+    }
+}
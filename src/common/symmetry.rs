@@ -0,0 +1,172 @@
+use texel_types::{Bounds, Position2D};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SymmetryAxis {
+    #[default]
+    Vertical, // mirrors left <-> right
+    Horizontal, // mirrors top <-> bottom
+    Both,
+}
+
+impl From<&str> for SymmetryAxis {
+    fn from(source: &str) -> Self {
+        match source {
+            "horizontal" | "h" => SymmetryAxis::Horizontal,
+            "both" | "b" => SymmetryAxis::Both,
+            _ => SymmetryAxis::Vertical,
+        }
+    }
+}
+
+pub const SYMMETRY_WORDS: [&str; 4] = ["off", "vertical", "horizontal", "both"];
+
+// describes mirrored drawing in edit/write modes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Symmetry {
+    pub axis: SymmetryAxis,
+    pub column: Option<i32>, // absolute axis column, sprite center if none
+    pub row: Option<i32>,    // absolute axis row, sprite center if none
+}
+
+impl Symmetry {
+    pub fn mirrors_x(&self) -> bool {
+        self.axis != SymmetryAxis::Horizontal
+    }
+
+    pub fn mirrors_y(&self) -> bool {
+        self.axis != SymmetryAxis::Vertical
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self.axis {
+            SymmetryAxis::Vertical => "[V]",
+            SymmetryAxis::Horizontal => "[H]",
+            SymmetryAxis::Both => "[VH]",
+        }
+    }
+
+    pub fn mirror_area(&self, area: Bounds, sprite_bounds: Bounds) -> Vec<(Position2D, bool, bool)> {
+        let positions: Vec<Position2D> = area.into_iter().collect();
+
+        self.mirror_positions(&positions, sprite_bounds)
+    }
+
+    // returns positions mirroring the given ones which are not already included
+    // with the (flip_x, flip_y) glyph transformation to use on each of them
+    pub fn mirror_positions(&self, positions: &[Position2D], sprite_bounds: Bounds) -> Vec<(Position2D, bool, bool)> {
+        // axes are kept doubled so we can mirror around the middle of a texel as well as between two
+        let sprite_pos = sprite_bounds.position();
+        let sprite_dim = sprite_bounds.dimension();
+        let axis_x = match self.column {
+            Some(x) => x * 2,
+            None => sprite_pos.x * 2 + i32::from(sprite_dim.w) - 1,
+        };
+        let axis_y = match self.row {
+            Some(y) => y * 2,
+            None => sprite_pos.y * 2 + i32::from(sprite_dim.h) - 1,
+        };
+
+        let mut flips = Vec::with_capacity(3);
+        if self.mirrors_x() {
+            flips.push((true, false));
+        }
+        if self.mirrors_y() {
+            flips.push((false, true));
+        }
+        if self.mirrors_x() && self.mirrors_y() {
+            flips.push((true, true));
+        }
+
+        let mut result: Vec<(Position2D, bool, bool)> = Vec::with_capacity(positions.len() * flips.len());
+        for pos in positions.iter() {
+            for (flip_x, flip_y) in flips.iter().copied() {
+                let mirrored = Position2D {
+                    x: if flip_x { axis_x - pos.x } else { pos.x },
+                    y: if flip_y { axis_y - pos.y } else { pos.y },
+                };
+
+                if !positions.contains(&mirrored) && !result.iter().any(|m| m.0 == mirrored) {
+                    result.push((mirrored, flip_x, flip_y));
+                }
+            }
+        }
+
+        result
+    }
+}
+
+// glyph mirroring, flip_x mirrors along a vertical axis, flip_y along a horizontal one
+pub fn mirror_symbol(symbol: char, flip_x: bool, flip_y: bool) -> char {
+    let mut result = symbol;
+
+    if flip_x {
+        result = mirror_symbol_x(result);
+    }
+
+    if flip_y {
+        result = mirror_symbol_y(result);
+    }
+
+    result
+}
+
+const MIRROR_X: [(char, char); 18] = [
+    ('/', '\\'),
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('<', '>'),
+    ('b', 'd'),
+    ('p', 'q'),
+    ('┌', '┐'),
+    ('└', '┘'),
+    ('├', '┤'),
+    ('╔', '╗'),
+    ('╚', '╝'),
+    ('╠', '╣'),
+    ('▌', '▐'),
+    ('◀', '▶'),
+    ('◢', '◣'),
+    ('◥', '◤'),
+    ('⌐', '¬'),
+];
+
+const MIRROR_Y: [(char, char); 17] = [
+    ('/', '\\'),
+    ('^', 'v'),
+    ('b', 'p'),
+    ('d', 'q'),
+    ('M', 'W'),
+    ('\'', ','),
+    ('_', '¯'),
+    ('┌', '└'),
+    ('┐', '┘'),
+    ('┬', '┴'),
+    ('╔', '╚'),
+    ('╗', '╝'),
+    ('╦', '╩'),
+    ('▀', '▄'),
+    ('▲', '▼'),
+    ('◢', '◥'),
+    ('◣', '◤'),
+];
+
+fn mirror_symbol_x(symbol: char) -> char {
+    mirror_from(symbol, &MIRROR_X)
+}
+
+fn mirror_symbol_y(symbol: char) -> char {
+    mirror_from(symbol, &MIRROR_Y)
+}
+
+fn mirror_from(symbol: char, pairs: &[(char, char)]) -> char {
+    for (a, b) in pairs {
+        if symbol == *a {
+            return *b;
+        } else if symbol == *b {
+            return *a;
+        }
+    }
+
+    symbol
+}
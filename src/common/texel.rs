@@ -1,3 +1,4 @@
+use crate::common::{ColorTable, ExtStyles};
 use crate::os::Terminal;
 use big_enum_set::BigEnumSet;
 
@@ -5,17 +6,36 @@ pub use texel_types::{SymbolStyle, Texel};
 
 // extra stuff useful only in texel itself
 pub trait TexelExt {
-    fn to_string(&self) -> String;
+    fn to_string(&self, colors: &ColorTable, ext_styles: ExtStyles, truecolor: bool) -> String;
+    fn sgr_string(&self, colors: &ColorTable, ext_styles: ExtStyles, truecolor: bool) -> String;
 }
 
 impl TexelExt for Texel {
-    fn to_string(&self) -> String {
+    fn to_string(&self, colors: &ColorTable, ext_styles: ExtStyles, truecolor: bool) -> String {
         format!(
-            "{}{}{}{}{}{}",
+            "{}{}",
             Terminal::goto(self.pos.x, self.pos.y),
-            Terminal::bg_color_sequence(self.bg),
-            Terminal::fg_color_sequence(self.fg),
+            self.sgr_string(colors, ext_styles, truecolor),
+        )
+    }
+
+    // colors, styles and symbol without positioning, exact colors only with truecolor
+    fn sgr_string(&self, colors: &ColorTable, ext_styles: ExtStyles, truecolor: bool) -> String {
+        let bg = match colors.get(self.bg).filter(|_| truecolor) {
+            Some(rgb) => Terminal::bg_rgb_sequence(rgb).to_string(),
+            None => Terminal::bg_color_sequence(self.bg).to_string(),
+        };
+        let fg = match colors.get(self.fg).filter(|_| truecolor) {
+            Some(rgb) => Terminal::fg_rgb_sequence(rgb).to_string(),
+            None => Terminal::fg_color_sequence(self.fg).to_string(),
+        };
+
+        format!(
+            "{}{}{}{}{}{}",
+            bg,
+            fg,
             styles_to_str(self.styles),
+            ext_styles_to_str(ext_styles),
             self.symbol,
             Terminal::reset_sequence(),
         )
@@ -26,9 +46,17 @@ fn styles_to_str(styles: BigEnumSet<SymbolStyle>) -> String {
     let mut result = String::with_capacity(64);
 
     for style in styles.iter() {
-        let sequence = Terminal::style_sequence(style);
+        result += &Terminal::style_sequence(style).to_string();
+    }
+
+    result
+}
 
-        result += &format!("{}{}", result, sequence);
+fn ext_styles_to_str(styles: ExtStyles) -> String {
+    let mut result = String::with_capacity(64);
+
+    for style in styles.iter() {
+        result += &Terminal::ext_style_sequence(style).to_string();
     }
 
     result
@@ -0,0 +1,50 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use texel_types::Position;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimelineChange {
+    Key,              // keyframe selected at current tick
+    Unkey(bool),      // remove keyframe of selected at current tick, all if true
+    Scrub(i32, bool), // tick, relative if true
+}
+
+pub const KEY_WORDS: [&str; 2] = ["clear", "all"];
+
+// object position keyframes, tick -> position
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keyframes(pub BTreeMap<usize, Position>);
+
+impl Keyframes {
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn last_tick(&self) -> usize {
+        self.0.keys().next_back().copied().unwrap_or_default()
+    }
+
+    // linear interpolation between surrounding keys, clamped to first and last
+    pub fn at(&self, tick: usize) -> Option<Position> {
+        let before = self.0.range(..=tick).next_back();
+        let after = self.0.range(tick..).next();
+
+        match (before, after) {
+            (Some((t0, p0)), Some((t1, p1))) if t1 > t0 => {
+                let step = (tick - t0) as f32 / (t1 - t0) as f32;
+
+                Some(Position {
+                    x: lerp(p0.x, p1.x, step),
+                    y: lerp(p0.y, p1.y, step),
+                    z: p0.z,
+                })
+            }
+            (Some((_, pos)), _) | (None, Some((_, pos))) => Some(*pos),
+            (None, None) => None,
+        }
+    }
+}
+
+fn lerp(from: i32, to: i32, step: f32) -> i32 {
+    from + ((to - from) as f32 * step).round() as i32
+}
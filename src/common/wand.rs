@@ -0,0 +1,102 @@
+use serde::{Deserialize, Serialize};
+use texel_types::{Position2D, Sprite, Texel};
+
+// how a magic wand result is combined with an existing mask
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MaskOp {
+    Replace,
+    Add,
+    Subtract,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WandMatch {
+    #[default]
+    Symbol,
+    Fg,
+    Bg,
+}
+
+impl From<&str> for WandMatch {
+    fn from(source: &str) -> Self {
+        match source {
+            "fg" => WandMatch::Fg,
+            "bg" => WandMatch::Bg,
+            _ => WandMatch::Symbol,
+        }
+    }
+}
+
+pub const WAND_WORDS: [&str; 5] = ["symbol", "fg", "bg", "contiguous", "global"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MagicWand {
+    pub matching: WandMatch,
+    pub contiguous: bool,
+}
+
+impl Default for MagicWand {
+    fn default() -> Self {
+        MagicWand {
+            matching: WandMatch::default(),
+            contiguous: true,
+        }
+    }
+}
+
+impl MagicWand {
+    // returns sprite relative positions of texels in the active frame
+    // matching the texel at given position, empty if there's none
+    pub fn select(&self, sprite: &Sprite, at: Position2D) -> Vec<Position2D> {
+        let origin = match sprite.read_texel(at) {
+            Some(texel) => texel,
+            None => return Vec::new(),
+        };
+
+        let matching: Vec<&Texel> = sprite.frame_iter().filter(|t| self.matches(origin, t)).collect();
+
+        if !self.contiguous {
+            return matching.into_iter().map(|t| t.pos).collect();
+        }
+
+        // flood fill over matching texels from origin
+        let mut result = vec![at];
+        let mut todo = vec![at];
+
+        while let Some(pos) = todo.pop() {
+            for diff in [(-1, 0), (1, 0), (0, -1), (0, 1)].iter() {
+                let next = pos + Position2D::from_xy(diff.0, diff.1);
+
+                if !result.contains(&next) && matching.iter().any(|t| t.pos == next) {
+                    result.push(next);
+                    todo.push(next);
+                }
+            }
+        }
+
+        result
+    }
+
+    fn matches(&self, origin: &Texel, other: &Texel) -> bool {
+        match self.matching {
+            WandMatch::Symbol => origin.symbol == other.symbol,
+            WandMatch::Fg => origin.fg == other.fg,
+            WandMatch::Bg => origin.bg == other.bg,
+        }
+    }
+}
+
+// combines given positions into the mask according to op
+pub fn apply_mask_op(mask: &mut Vec<Position2D>, positions: Vec<Position2D>, op: MaskOp) {
+    match op {
+        MaskOp::Replace => *mask = positions,
+        MaskOp::Add => {
+            for pos in positions {
+                if !mask.contains(&pos) {
+                    mask.push(pos);
+                }
+            }
+        }
+        MaskOp::Subtract => mask.retain(|p| !positions.contains(p)),
+    }
+}
@@ -18,8 +18,48 @@ impl Subselection {
     }
 }
 
+// texel level selection, absolute positions
+#[derive(Default, Clone, PartialEq, Eq)]
+pub struct SelectionMask(pub Vec<Position2D>);
+
+impl SelectionMask {
+    pub fn bounds(&self) -> Option<Bounds> {
+        let first = self.0.first()?;
+        let mut top_left = *first;
+        let mut bottom_right = *first;
+
+        for pos in self.0.iter() {
+            top_left.x = std::cmp::min(top_left.x, pos.x);
+            top_left.y = std::cmp::min(top_left.y, pos.y);
+            bottom_right.x = std::cmp::max(bottom_right.x, pos.x);
+            bottom_right.y = std::cmp::max(bottom_right.y, pos.y);
+        }
+
+        Some(top_left.area(bottom_right))
+    }
+}
+
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
 pub struct Selectable;
 
+// skipped by rendering and export
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct Hidden;
+
+// skipped by selection and translation
+#[derive(Default, Clone, Copy, PartialEq, Eq)]
+pub struct Locked;
+
 #[derive(Default, Clone, Copy, PartialEq, Eq)]
 pub struct Bookmark(pub usize);
+
+// innermost group an object belongs to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Group(pub usize);
+
+// group hierarchy, parent is None for top level groups
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GroupInfo {
+    pub id: usize,
+    pub parent: Option<usize>,
+}
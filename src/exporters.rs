@@ -1,7 +1,13 @@
+mod ansi;
 mod plaintext;
 
+pub use ansi::Ansi;
 pub use plaintext::Plaintext;
 
 pub trait Exporter {
-    fn export(scene: texel_types::Scene, output: &mut impl std::io::Write) -> Result<(), std::io::Error>;
+    fn export(
+        scene: crate::common::Scene,
+        tick: usize, // timeline tick to place keyed objects at
+        output: &mut impl std::io::Write,
+    ) -> Result<(), std::io::Error>;
 }
@@ -0,0 +1,66 @@
+use crate::common::{ExtStyles, Scene, TexelExt};
+use crate::exporters::Exporter;
+use std::io::{Error, Write};
+use texel_types::Texel;
+
+pub struct Ansi;
+
+type Line = Vec<Option<(Texel, ExtStyles)>>; // empty cells stay unstyled spaces
+type Lines = Vec<Line>;
+
+impl Exporter for Ansi {
+    fn export(scene: Scene, tick: usize, output: &mut impl Write) -> Result<(), Error> {
+        let mut lines: Lines = Vec::with_capacity(256);
+        let current = scene.current().at_tick(tick);
+        let colors = current.colors;
+
+        let mut sorted = current.objects;
+        sorted.sort_by_key(|o| std::cmp::Reverse(o.1.z)); // same order as sprite renderer
+
+        for obj in sorted {
+            let pos = obj.1;
+            let sprite = obj.0;
+            let ext_styles = obj.2.ext_styles;
+
+            for texel in sprite.frame_iter() {
+                let abs_pos = pos + texel.pos;
+                if abs_pos.x < 0 || abs_pos.y < 0 {
+                    continue;
+                }
+
+                let col = abs_pos.x as usize;
+                let row = abs_pos.y as usize;
+
+                fill_to_pos(&mut lines, row, col);
+
+                lines[row][col] = Some((texel.clone(), ext_styles.get(sprite.index, texel.pos)));
+            }
+        }
+
+        for line in lines {
+            let mut str_line = String::with_capacity(line.len() * 32);
+
+            for cell in line {
+                match cell {
+                    Some((texel, ext_styles)) => str_line += &texel.sgr_string(&colors, ext_styles, true),
+                    None => str_line.push(' '),
+                }
+            }
+            str_line.push('\n');
+
+            output.write_all(str_line.as_bytes())?;
+        }
+
+        Ok(())
+    }
+}
+
+fn fill_to_pos(lines: &mut Lines, row: usize, col: usize) {
+    while lines.len() <= row {
+        lines.push(Line::with_capacity(256));
+    }
+
+    while lines[row].len() <= col {
+        lines[row].push(None);
+    }
+}
@@ -1,6 +1,6 @@
+use crate::common::Scene;
 use crate::exporters::Exporter;
 use std::io::{Error, Write};
-use texel_types::Scene;
 
 pub struct Plaintext;
 
@@ -8,14 +8,14 @@ type Line = Vec<char>; // we need to override at position and this is way more e
 type Lines = Vec<Line>;
 
 impl Exporter for Plaintext {
-    fn export(scene: Scene, output: &mut impl Write) -> Result<(), Error> {
+    fn export(scene: Scene, tick: usize, output: &mut impl Write) -> Result<(), Error> {
         let mut lines: Lines = Vec::with_capacity(256);
         let mut str_line = String::with_capacity(1024); // we should be able to fit this
         let mut new_line = [0u8; 4];
-        let new_line_len = '\n'.encode_utf8(&mut new_line).bytes().len();
+        let new_line_len = '\n'.encode_utf8(&mut new_line).len();
 
-        let mut sorted = scene.current().objects;
-        sorted.sort_by(|o1, o2| o1.1.z.cmp(&o2.1.z));
+        let mut sorted = scene.current().at_tick(tick).objects;
+        sorted.sort_by_key(|o| o.1.z);
 
         for obj in sorted {
             let pos = obj.1;
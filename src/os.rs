@@ -17,3 +17,11 @@ mod tty_crossterm;
 pub use input_crossterm::InputSource;
 #[cfg(not(feature = "ion"))]
 pub use tty_crossterm::Terminal;
+
+// terminals advertise 24-bit color support via COLORTERM
+fn truecolor_env() -> bool {
+    match std::env::var("COLORTERM") {
+        Ok(value) => value == "truecolor" || value == "24bit",
+        Err(_) => false,
+    }
+}
@@ -1,6 +1,9 @@
-use crate::common::{CharMap, Event, InputEvent, Mode, ModesCharMap, MoveMeta};
-use crossterm::event::{read, Event as TEvent, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEventKind};
+use crate::common::{CharMap, Event, InputEvent, MaskOp, Mode, ModesCharMap, MoveMeta};
+use crossterm::event::{
+    poll, read, Event as TEvent, KeyCode as Key, KeyEvent, KeyModifiers, MouseButton, MouseEventKind,
+};
 use std::collections::HashMap;
+use std::time::Duration;
 use texel_types::{ColorMode, Position2D, Which};
 
 type RawMap = HashMap<TEvent, Event>;
@@ -23,6 +26,15 @@ impl InputSource {
         }
     }
 
+    // returns None if no input arrived within timeout
+    pub fn poll_event(&self, mode: Mode, timeout: Duration) -> Option<InputEvent> {
+        match poll(timeout) {
+            Err(err) => panic!("{}", err),
+            Ok(true) => Some(self.next_event(mode)),
+            Ok(false) => None,
+        }
+    }
+
     fn map_input(&self, raw_event: TEvent, map: &RawMap) -> InputEvent {
         let mapped = map.get(&raw_event).copied().unwrap_or_else(|| match raw_event {
             TEvent::Resize(_, _) => Event::Resize,
@@ -108,6 +120,10 @@ fn default_map(cm: &CharMap) -> RawMap {
         TEvent::Key(KeyEvent::new(Key::Char('x'), KeyModifiers::ALT)),
         Event::PickColor(ColorMode::Bg),
     );
+    result.insert(
+        TEvent::Key(KeyEvent::new(Key::Char('g'), KeyModifiers::ALT)),
+        Event::MagicWand(MaskOp::Subtract),
+    );
     result.insert(
         TEvent::Key(KeyEvent::new(Key::BackTab, KeyModifiers::SHIFT)),
         Event::SelectObject(Which::Next, true),
@@ -1,6 +1,8 @@
-use crate::common::{CharMap, Event, InputEvent, Mode, ModesCharMap, MoveMeta};
+use crate::common::{CharMap, Event, InputEvent, MaskOp, Mode, ModesCharMap, MoveMeta};
 use std::collections::HashMap;
 use std::io::stdin;
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
 use termion::event::{Event as TEvent, Key, MouseButton, MouseEvent};
 use termion::input::TermRead;
 use texel_types::{ColorMode, Position2D, Which};
@@ -9,6 +11,7 @@ type RawMap = HashMap<TEvent, Event>;
 
 pub struct InputSource {
     mode_maps: Vec<RawMap>,
+    events: Receiver<std::io::Result<TEvent>>, // fed by blocking stdin reader thread
 }
 
 impl InputSource {
@@ -18,9 +21,23 @@ impl InputSource {
             .get(mode.index())
             .unwrap_or_else(|| panic!("Mode map not found"));
 
-        match stdin().events().next() {
-            None => panic!("Error on input"),
-            Some(result) => self.map_input(result.unwrap(), map),
+        match self.events.recv() {
+            Ok(result) => self.map_input(result.unwrap(), map),
+            Err(_) => panic!("Error on input"),
+        }
+    }
+
+    // returns None if no input arrived within timeout
+    pub fn poll_event(&self, mode: Mode, timeout: Duration) -> Option<InputEvent> {
+        let map = self
+            .mode_maps
+            .get(mode.index())
+            .unwrap_or_else(|| panic!("Mode map not found"));
+
+        match self.events.recv_timeout(timeout) {
+            Ok(result) => Some(self.map_input(result.unwrap(), map)),
+            Err(RecvTimeoutError::Timeout) => None,
+            Err(RecvTimeoutError::Disconnected) => panic!("Error on input"),
         }
     }
 
@@ -48,8 +65,18 @@ impl InputSource {
 
 impl From<ModesCharMap> for InputSource {
     fn from(cm: ModesCharMap) -> Self {
+        let (sender, receiver) = channel();
+        std::thread::spawn(move || {
+            for event in stdin().events() {
+                if sender.send(event).is_err() {
+                    break; // input source is gone
+                }
+            }
+        });
+
         let mut result = InputSource {
             mode_maps: Vec::with_capacity(Mode::count()),
+            events: receiver,
         };
 
         let defaults = default_map(cm.all_modes());
@@ -85,6 +112,7 @@ fn default_map(cm: &CharMap) -> RawMap {
     result.insert(TEvent::Key(Key::Ctrl('l')), Event::Down(MoveMeta::Alternative));
     result.insert(TEvent::Key(Key::Alt('z')), Event::PickColor(ColorMode::Fg));
     result.insert(TEvent::Key(Key::Alt('x')), Event::PickColor(ColorMode::Bg));
+    result.insert(TEvent::Key(Key::Alt('g')), Event::MagicWand(MaskOp::Subtract));
     result.insert(TEvent::Key(Key::BackTab), Event::SelectObject(Which::Next, true));
 
     result
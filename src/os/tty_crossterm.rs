@@ -1,3 +1,4 @@
+use crate::common::{ExtStyle, Rgb};
 use crossterm::terminal::size as crossterm_size;
 use crossterm::ExecutableCommand;
 use std::io::{Stdout, Write};
@@ -66,6 +67,18 @@ impl Terminal {
         }
     }
 
+    pub fn ext_style_sequence(style: ExtStyle) -> &'static dyn std::fmt::Display {
+        use crossterm::style::{Attribute, SetAttribute};
+
+        match style {
+            ExtStyle::Dim => &SetAttribute(Attribute::Dim),
+            ExtStyle::Reverse => &SetAttribute(Attribute::Reverse),
+            ExtStyle::Blink => &SetAttribute(Attribute::SlowBlink),
+            ExtStyle::Strikethrough => &SetAttribute(Attribute::CrossedOut),
+            ExtStyle::DoubleUnderline => &SetAttribute(Attribute::DoubleUnderlined),
+        }
+    }
+
     pub fn rgb_u8(r: u8, g: u8, b: u8) -> u8 {
         16 + 36 * r + 6 * g + b
     }
@@ -86,6 +99,30 @@ impl Terminal {
         crossterm::style::SetForegroundColor(localized)
     }
 
+    pub fn bg_rgb_sequence(color: Rgb) -> impl std::fmt::Display {
+        let localized = crossterm::style::Color::Rgb {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        };
+
+        crossterm::style::SetBackgroundColor(localized)
+    }
+
+    pub fn fg_rgb_sequence(color: Rgb) -> impl std::fmt::Display {
+        let localized = crossterm::style::Color::Rgb {
+            r: color.r,
+            g: color.g,
+            b: color.b,
+        };
+
+        crossterm::style::SetForegroundColor(localized)
+    }
+
+    pub fn truecolor() -> bool {
+        super::truecolor_env()
+    }
+
     pub fn blank_to_black(&mut self) {
         let ts = Self::terminal_size();
         let empty_line = " ".repeat(usize::from(ts.0));
@@ -1,3 +1,4 @@
+use crate::common::{ExtStyle, Rgb};
 use std::io::{Stdout, Write};
 use termion::input::MouseTerminal;
 use termion::raw::IntoRawMode;
@@ -58,6 +59,16 @@ impl Terminal {
         }
     }
 
+    pub fn ext_style_sequence(style: ExtStyle) -> &'static dyn std::fmt::Display {
+        match style {
+            ExtStyle::Dim => &termion::style::Faint,
+            ExtStyle::Reverse => &termion::style::Invert,
+            ExtStyle::Blink => &termion::style::Blink,
+            ExtStyle::Strikethrough => &termion::style::CrossedOut,
+            ExtStyle::DoubleUnderline => &DoubleUnderline,
+        }
+    }
+
     pub fn rgb_u8(r: u8, g: u8, b: u8) -> u8 {
         termion::color::AnsiValue::rgb(r, g, b).0
     }
@@ -74,6 +85,18 @@ impl Terminal {
         termion::color::AnsiValue(color).fg_string()
     }
 
+    pub fn bg_rgb_sequence(color: Rgb) -> impl std::fmt::Display {
+        termion::color::Rgb(color.r, color.g, color.b).bg_string()
+    }
+
+    pub fn fg_rgb_sequence(color: Rgb) -> impl std::fmt::Display {
+        termion::color::Rgb(color.r, color.g, color.b).fg_string()
+    }
+
+    pub fn truecolor() -> bool {
+        super::truecolor_env()
+    }
+
     pub fn blank_to_black(&mut self) {
         let ts = Self::terminal_size();
         let empty_line = " ".repeat(usize::from(ts.0));
@@ -92,3 +115,12 @@ impl Terminal {
         }
     }
 }
+
+// termion has no double underline, SGR 4:2 same as crossterm
+struct DoubleUnderline;
+
+impl std::fmt::Display for DoubleUnderline {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "\x1B[4:2m")
+    }
+}
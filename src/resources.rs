@@ -5,5 +5,5 @@ mod state;
 
 pub use cmdline::CmdLine;
 pub use framebuffer::FrameBuffer;
-pub use palette::{ColorPalette, SymbolPalette, MAX_COLOR_INDEX, PALETTE_H, PALETTE_OFFSET, PALETTE_W};
-pub use state::State;
+pub use palette::{ColorPalette, SymbolPalette, PALETTE_H, PALETTE_OFFSET, PALETTE_W};
+pub use state::{History, State};
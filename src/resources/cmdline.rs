@@ -1,7 +1,12 @@
-use crate::common::{fio, topic_index, Action, Error, Event, InputEvent, Layout, MetadataType, Mode};
-use crate::components::Translation;
+use crate::common::{
+    fio, topic_index, Action, Alignment, Anchor, ColorDepth, ColorRemap, Distribution, Error, Event, FrameOp,
+    GridChange, GuideChange, InputEvent, Layout, MagicWand, MetadataType, Mode, OnionSkin, PaletteOp, Playback,
+    RemapOp, RemapSource, Rgb, RgbTarget, Symmetry, SymmetryAxis, TimelineChange, WandMatch,
+};
+use crate::components::{Dimension, Position2D, Translation};
 use std::iter::Peekable;
 use std::str::SplitAsciiWhitespace;
+use texel_types::ColorMode;
 
 mod auto_complete;
 
@@ -171,6 +176,9 @@ impl CmdLine {
                 "export" | "read" | "write" | "w" | "r" => {
                     self.auto_complete.complete_filename(parts.last().unwrap_or(&"."))?
                 }
+                "set" if parts.get(1) == Some(&"loop") && parts.len() > 2 => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::LOOP_MODE_WORDS),
                 "set" => self
                     .auto_complete
                     .complete_from_list(parts.last().unwrap_or(&""), &crate::common::METADATA_TYPES),
@@ -180,6 +188,52 @@ impl CmdLine {
                 "layout" => self
                     .auto_complete
                     .complete_from_list(parts.last().unwrap_or(&""), &crate::common::LAYOUT_WORDS),
+                "align" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::ALIGNMENT_WORDS),
+                "distribute" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::DISTRIBUTE_WORDS),
+                "grid" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::GRID_WORDS),
+                "guide" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::GUIDE_WORDS),
+                "play" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::PLAYBACK_WORDS),
+                "canvas" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::ANCHOR_WORDS),
+                "wand" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::WAND_WORDS),
+                "symmetry" | "sym" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::SYMMETRY_WORDS),
+                "key" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::KEY_WORDS),
+                "palette" if parts.len() > 2 => self.auto_complete.complete_filename(parts.last().unwrap_or(&"."))?,
+                "palette" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::PALETTE_WORDS),
+                "frame" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::FRAME_WORDS),
+                "quantize" | "preview" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::DEPTH_WORDS),
+                "remap" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::REMAP_WORDS),
+                "onion" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::ONION_WORDS),
+                "recover" => self
+                    .auto_complete
+                    .complete_from_list(parts.last().unwrap_or(&""), &crate::common::RECOVER_WORDS),
                 _ => None,
             } {
                 match completion {
@@ -206,12 +260,27 @@ impl CmdLine {
         match action {
             Action::New(_)
             | Action::ClearBlank
+            | Action::Trim
+            | Action::Group
+            | Action::Ungroup
+            | Action::EnterGroup
+            | Action::LeaveGroup
+            | Action::Hide
+            | Action::Show
+            | Action::Lock
+            | Action::Unlock
+            | Action::Grid(GridChange::ToggleSnap)
+            | Action::ToggleRulers
+            | Action::ToggleCrosshair
             | Action::Deselect
             | Action::Tutorial
             | Action::Delete
             | Action::ToggleMetadata
+            | Action::UndoList(_)
             | Action::SetMode(_) => Ok(action),
             Action::SetMetadata(_) => self.parse_set_metadata(parts),
+            Action::Layout(Layout::Align(_, _)) => self.parse_align(parts),
+            Action::Layout(Layout::Distribute(_, _)) => self.parse_distribute(parts),
             Action::Layout(_) => self.parse_layout(parts),
             Action::Duplicate(_) => self.parse_duplicate(parts),
             Action::Translate(_) => self.parse_translate(parts),
@@ -219,7 +288,25 @@ impl CmdLine {
             Action::WriteAndQuit(_) => self.parse_save_and_quit(parts),
             Action::Read(_) => self.parse_load(parts),
             Action::ShowHelp(_) => self.parse_help(parts),
-            Action::Export(_, _) => self.parse_export(parts),
+            Action::Export(_, _, _, _) => self.parse_export(parts),
+            Action::ToggleSymmetry => self.parse_symmetry(parts),
+            Action::ToggleOnionSkin => self.parse_onion(parts),
+            Action::Outline(_) => self.parse_outline(parts),
+            Action::Shadow(_, _) => self.parse_shadow(parts),
+            Action::SetWand(_) => self.parse_wand(parts),
+            Action::Canvas(_, _) => self.parse_canvas(parts),
+            Action::Grid(_) => self.parse_grid(parts),
+            Action::Guide(_) => self.parse_guide(parts),
+            Action::Play(_) => self.parse_play(parts),
+            Action::Frame(_) => self.parse_frame(parts),
+            Action::Timeline(TimelineChange::Key) => self.parse_key(parts),
+            Action::Timeline(_) => self.parse_tick(parts),
+            Action::SetRgb(_, _) => self.parse_rgb(parts),
+            Action::Palette(_) => self.parse_palette(parts),
+            Action::Quantize(_) => self.parse_quantize(parts),
+            Action::Preview(_) => self.parse_preview(parts),
+            Action::Remap(_) => self.parse_remap(parts),
+            Action::Recover(_) => self.parse_recover(parts),
             _ => Err(Error::InvalidCommand),
         }
     }
@@ -232,6 +319,9 @@ impl CmdLine {
         match mt_str {
             "id" => self.parse_set_id(parts),
             "labels" => self.parse_set_labels(parts),
+            "duration" => self.parse_set_duration(parts),
+            "loop" => self.parse_set_loop(parts),
+            "anim" => self.parse_set_range(parts),
             _ => Err(Error::execution("Invalid metadata type")),
         }
     }
@@ -250,44 +340,130 @@ impl CmdLine {
         Ok(Action::SetMetadata(mt))
     }
 
+    fn parse_set_duration(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let arg = parts.next().unwrap_or("none");
+        let mt = MetadataType::parse_duration(arg)?;
+
+        Ok(Action::SetMetadata(mt))
+    }
+
+    fn parse_set_loop(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let arg = parts.next().unwrap_or("loop");
+        let mt = MetadataType::parse_loop(arg)?;
+
+        Ok(Action::SetMetadata(mt))
+    }
+
+    fn parse_set_range(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let name = parts.next().ok_or(Error::InvalidParam("No animation name specified"))?;
+        let arg = parts.next().unwrap_or("none");
+        let mt = MetadataType::parse_range(name, arg)?;
+
+        Ok(Action::SetMetadata(mt))
+    }
+
     fn parse_layout(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
         let type_str = parts.next().unwrap_or("none");
 
         match Layout::from(type_str) {
             Layout::Random => Ok(Action::Layout(Layout::Random)),
-            Layout::Column(_, _) => {
-                let cols = parts
-                    .next()
-                    .ok_or(Error::InvalidParam("No columns specified"))?
-                    .parse::<usize>()
-                    .map_err(|_| Error::InvalidParam("Invalid columns value"))?;
-                let padding_x = parts
-                    .next()
-                    .ok_or(Error::InvalidParam("No padding specified"))?
-                    .parse::<u16>()
-                    .map_err(|_| Error::InvalidParam("Invalid padding X value"))?;
-                let padding_y = match parts.next() {
-                    None => padding_x,
-                    Some(str_y) => str_y
+            Layout::Row(_) => {
+                let padding = match parts.next() {
+                    None => 1,
+                    Some(str_padding) => str_padding
                         .parse::<u16>()
-                        .map_err(|_| Error::InvalidParam("Invalid padding Y value"))?,
+                        .map_err(|_| Error::InvalidParam("Invalid padding value"))?,
                 };
 
-                if cols == 0 {
-                    return Err(Error::InvalidParam("Columns must be positive"));
-                }
-
-                if padding_x == 0 {
+                if padding == 0 {
                     return Err(Error::InvalidParam("Padding must be positive"));
                 }
 
-                let padding = (padding_x, padding_y);
+                Ok(Action::Layout(Layout::Row(padding)))
+            }
+            Layout::Column(_, _) => {
+                let (cols, padding) = self.parse_cells(parts)?;
                 Ok(Action::Layout(Layout::Column(cols, padding)))
             }
-            Layout::None => Err(Error::InvalidParam("Invalid layout type")),
+            Layout::Grid(_, _) => {
+                let (cols, padding) = self.parse_cells(parts)?;
+                Ok(Action::Layout(Layout::Grid(cols, padding)))
+            }
+            Layout::None | Layout::Align(_, _) | Layout::Distribute(_, _) => {
+                Err(Error::InvalidParam("Invalid layout type"))
+            }
         }
     }
 
+    fn parse_cells(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<(usize, (u16, u16)), Error> {
+        let cols = parts
+            .next()
+            .ok_or(Error::InvalidParam("No columns specified"))?
+            .parse::<usize>()
+            .map_err(|_| Error::InvalidParam("Invalid columns value"))?;
+        let padding_x = parts
+            .next()
+            .ok_or(Error::InvalidParam("No padding specified"))?
+            .parse::<u16>()
+            .map_err(|_| Error::InvalidParam("Invalid padding X value"))?;
+        let padding_y = match parts.next() {
+            None => padding_x,
+            Some(str_y) => str_y
+                .parse::<u16>()
+                .map_err(|_| Error::InvalidParam("Invalid padding Y value"))?,
+        };
+
+        if cols == 0 {
+            return Err(Error::InvalidParam("Columns must be positive"));
+        }
+
+        if padding_x == 0 {
+            return Err(Error::InvalidParam("Padding must be positive"));
+        }
+
+        Ok((cols, (padding_x, padding_y)))
+    }
+
+    fn parse_align(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let alignment = Alignment::parse(parts.next().ok_or(Error::InvalidParam("No alignment specified"))?)
+            .ok_or(Error::InvalidParam("Invalid alignment"))?;
+        let to_viewport = match parts.next() {
+            None => false,
+            Some("viewport") => true,
+            Some(_) => return Err(Error::InvalidParam("Invalid alignment target")),
+        };
+
+        Ok(Action::Layout(Layout::Align(alignment, to_viewport)))
+    }
+
+    fn parse_distribute(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let horizontal = match parts.next() {
+            Some("horizontal") | Some("h") => true,
+            Some("vertical") | Some("v") => false,
+            Some(_) => return Err(Error::InvalidParam("Invalid distribution direction")),
+            None => return Err(Error::InvalidParam("No distribution direction specified")),
+        };
+
+        let distribution = match parts.next() {
+            None => Distribution::Even,
+            Some(type_str) => {
+                let size = parts
+                    .next()
+                    .ok_or(Error::InvalidParam("No size specified"))?
+                    .parse::<u16>()
+                    .map_err(|_| Error::InvalidParam("Invalid size value"))?;
+
+                match type_str {
+                    "spacing" => Distribution::Spacing(size),
+                    "pitch" => Distribution::Pitch(size),
+                    _ => return Err(Error::InvalidParam("Invalid distribution type")),
+                }
+            }
+        };
+
+        Ok(Action::Layout(Layout::Distribute(horizontal, distribution)))
+    }
+
     fn parse_duplicate(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
         let count = parts
             .next()
@@ -349,9 +525,391 @@ impl CmdLine {
 
     fn parse_export(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
         if let Some(path) = parts.next() {
-            return Ok(Action::Export(fio::ExportFormat::default(), String::from(path)));
+            let mut include_hidden = false;
+            let mut depth = None;
+
+            for option in parts {
+                match option {
+                    "hidden" => include_hidden = true,
+                    _ => {
+                        depth =
+                            Some(ColorDepth::parse(option).map_err(|_| Error::InvalidParam("Invalid export option"))?)
+                    }
+                }
+            }
+
+            return Ok(Action::Export(
+                fio::ExportFormat::from_path(path),
+                String::from(path),
+                include_hidden,
+                depth,
+            ));
         }
 
         Err(Error::InvalidParam("No path specified"))
     }
+
+    fn parse_quantize(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        match parts.next() {
+            Some(depth_str) => Ok(Action::Quantize(ColorDepth::parse(depth_str)?)),
+            None => Err(Error::InvalidParam("No color depth specified")),
+        }
+    }
+
+    fn parse_preview(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        match parts.next() {
+            None | Some("off") => Ok(Action::Preview(None)),
+            Some(depth_str) => Ok(Action::Preview(Some(ColorDepth::parse(depth_str)?))),
+        }
+    }
+
+    fn parse_recover(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        match parts.next() {
+            None => Ok(Action::Recover(false)),
+            Some("discard") => Ok(Action::Recover(true)),
+            Some(_) => Err(Error::InvalidParam("Invalid recover option, use discard")),
+        }
+    }
+
+    // remap <from=to,..|name> [fg|bg] [all], remap save <name> <from=to,..>, remap delete <name>
+    fn parse_remap(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let source = match parts.next() {
+            Some("save") => {
+                let name = parts.next().ok_or(Error::InvalidParam("Missing remap name"))?;
+                let remap = ColorRemap::parse(parts.next().ok_or(Error::InvalidParam("Missing remap colors"))?)?;
+
+                return Ok(Action::Remap(RemapOp::Save(name.into(), remap)));
+            }
+            Some("delete") => {
+                let name = parts.next().ok_or(Error::InvalidParam("Missing remap name"))?;
+
+                return Ok(Action::Remap(RemapOp::Delete(name.into())));
+            }
+            Some(pairs) if pairs.contains('=') => RemapSource::Inline(ColorRemap::parse(pairs)?),
+            Some(name) => RemapSource::Named(name.into()),
+            None => return Err(Error::InvalidParam("Missing remap colors or name")),
+        };
+
+        let mut cm = None;
+        let mut all_frames = false;
+        for option in parts {
+            match option {
+                "fg" => cm = Some(ColorMode::Fg),
+                "bg" => cm = Some(ColorMode::Bg),
+                "all" => all_frames = true,
+                _ => return Err(Error::InvalidParam("Invalid remap option")),
+            }
+        }
+
+        Ok(Action::Remap(RemapOp::Apply(source, cm, all_frames)))
+    }
+
+    fn parse_symmetry(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let axis_str = match parts.next() {
+            None => return Ok(Action::ToggleSymmetry),
+            Some("off") => return Ok(Action::Symmetry(None)),
+            Some(axis_str) => axis_str,
+        };
+
+        let axis = match axis_str {
+            "vertical" | "v" | "horizontal" | "h" | "both" | "b" => SymmetryAxis::from(axis_str),
+            _ => return Err(Error::InvalidParam("Invalid symmetry axis")),
+        };
+
+        let mut symmetry = Symmetry {
+            axis,
+            column: None,
+            row: None,
+        };
+
+        if axis != SymmetryAxis::Horizontal {
+            symmetry.column = match parts.next() {
+                None => None,
+                Some(str_x) => Some(
+                    str_x
+                        .parse::<i32>()
+                        .map_err(|_| Error::InvalidParam("Invalid column value"))?,
+                ),
+            };
+        }
+
+        if axis != SymmetryAxis::Vertical {
+            symmetry.row = match parts.next() {
+                None => None,
+                Some(str_y) => Some(
+                    str_y
+                        .parse::<i32>()
+                        .map_err(|_| Error::InvalidParam("Invalid row value"))?,
+                ),
+            };
+        }
+
+        Ok(Action::Symmetry(Some(symmetry)))
+    }
+
+    fn parse_frame(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let op = match parts.next() {
+            None | Some("dup") => FrameOp::Duplicate,
+            Some("left") => FrameOp::Move(false),
+            Some("right") => FrameOp::Move(true),
+            Some("copy") => FrameOp::Copy,
+            Some("paste") => FrameOp::Paste,
+            Some("insert") => FrameOp::Insert(self.parse_frame_index(parts.next())?),
+            Some("swap") => {
+                let a = self.parse_frame_index(parts.next())?;
+                let b = self.parse_frame_index(parts.next())?;
+
+                FrameOp::Swap(a, b)
+            }
+            Some(_) => return Err(Error::InvalidParam("Invalid frame operation")),
+        };
+
+        Ok(Action::Frame(op))
+    }
+
+    fn parse_rgb(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let rgb = Rgb::parse(parts.next().ok_or(Error::InvalidParam("Missing RGB color"))?)?;
+        let target = match parts.next() {
+            None | Some("fg") => RgbTarget::Color(ColorMode::Fg),
+            Some("bg") => RgbTarget::Color(ColorMode::Bg),
+            Some(selector) => {
+                // same selectors as shown on the palette
+                let index = "1234567890abcdef"
+                    .find(selector)
+                    .filter(|_| selector.len() == 1)
+                    .ok_or(Error::InvalidParam("Invalid palette index"))?;
+
+                RgbTarget::Palette(index)
+            }
+        };
+
+        Ok(Action::SetRgb(rgb, target))
+    }
+
+    fn parse_palette(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let op_str = parts.next();
+        let path = parts.next().ok_or(Error::InvalidParam("Missing palette file"));
+
+        match op_str {
+            Some("load") => Ok(Action::Palette(PaletteOp::Load(path?.into()))),
+            Some("save") => Ok(Action::Palette(PaletteOp::Save(path?.into()))),
+            _ => Err(Error::InvalidParam("Invalid palette operation")),
+        }
+    }
+
+    fn parse_key(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        match (parts.next(), parts.next()) {
+            (None, _) => Ok(Action::Timeline(TimelineChange::Key)),
+            (Some("clear"), None) => Ok(Action::Timeline(TimelineChange::Unkey(false))),
+            (Some("clear"), Some("all")) => Ok(Action::Timeline(TimelineChange::Unkey(true))),
+            _ => Err(Error::InvalidParam("Invalid key option")),
+        }
+    }
+
+    // absolute tick or relative with + or - prefix, start of timeline if none
+    fn parse_tick(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let tick_str = parts.next().unwrap_or("0");
+        let relative = tick_str.starts_with('+') || tick_str.starts_with('-');
+        let tick = tick_str
+            .parse::<i32>()
+            .map_err(|_| Error::InvalidParam("Invalid tick value"))?;
+
+        Ok(Action::Timeline(TimelineChange::Scrub(tick, relative)))
+    }
+
+    // frames are numbered from 1 as in the status line
+    fn parse_frame_index(&self, part: Option<&str>) -> Result<usize, Error> {
+        let number = part
+            .ok_or(Error::InvalidParam("Missing frame number"))?
+            .parse::<usize>()
+            .map_err(|_| Error::InvalidParam("Invalid frame number"))?;
+
+        if number == 0 {
+            return Err(Error::InvalidParam("Invalid frame number"));
+        }
+
+        Ok(number - 1)
+    }
+
+    fn parse_onion(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let (previous, next) = match parts.next() {
+            None => return Ok(Action::ToggleOnionSkin),
+            Some("off") => return Ok(Action::OnionSkin(None)),
+            Some("previous") | Some("prev") => (true, false),
+            Some("next") => (false, true),
+            Some("both") => (true, true),
+            Some(_) => return Err(Error::InvalidParam("Invalid onion skin direction")),
+        };
+
+        let depth = match parts.next() {
+            None => 1,
+            Some(str_depth) => str_depth
+                .parse::<usize>()
+                .map_err(|_| Error::InvalidParam("Invalid onion skin depth"))?,
+        };
+
+        if depth == 0 {
+            return Err(Error::InvalidParam("Invalid onion skin depth"));
+        }
+
+        Ok(Action::OnionSkin(Some(OnionSkin { previous, next, depth })))
+    }
+
+    fn parse_outline(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let symbol = match parts.next() {
+            None => '#',
+            Some(str_symbol) => {
+                let mut chars = str_symbol.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => c,
+                    _ => return Err(Error::InvalidParam("Outline symbol must be a single character")),
+                }
+            }
+        };
+
+        Ok(Action::Outline(symbol))
+    }
+
+    fn parse_shadow(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let dx = parts
+            .next()
+            .unwrap_or("1")
+            .parse::<i32>()
+            .map_err(|_| Error::InvalidParam("Invalid X offset value"))?;
+        let dy = parts
+            .next()
+            .unwrap_or("1")
+            .parse::<i32>()
+            .map_err(|_| Error::InvalidParam("Invalid Y offset value"))?;
+        let as_sprite = match parts.next() {
+            None => false,
+            Some("new") => true,
+            Some(_) => {
+                return Err(Error::InvalidParam(
+                    "Invalid shadow target, use 'new' for separate sprite",
+                ))
+            }
+        };
+
+        Ok(Action::Shadow(Position2D::from_xy(dx, dy), as_sprite))
+    }
+
+    fn parse_wand(&self, parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let mut wand = MagicWand::default();
+
+        for part in parts {
+            match part {
+                "symbol" | "fg" | "bg" => wand.matching = WandMatch::from(part),
+                "contiguous" => wand.contiguous = true,
+                "global" => wand.contiguous = false,
+                _ => return Err(Error::InvalidParam("Invalid wand parameter")),
+            }
+        }
+
+        Ok(Action::SetWand(wand))
+    }
+
+    fn parse_canvas(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let w = parts
+            .next()
+            .ok_or(Error::InvalidParam("No width specified"))?
+            .parse::<u16>()
+            .map_err(|_| Error::InvalidParam("Invalid width value"))?;
+        let h = parts
+            .next()
+            .ok_or(Error::InvalidParam("No height specified"))?
+            .parse::<u16>()
+            .map_err(|_| Error::InvalidParam("Invalid height value"))?;
+        let anchor = match parts.next() {
+            None => Anchor::default(),
+            Some(str_anchor) => Anchor::parse(str_anchor).ok_or(Error::InvalidParam("Invalid anchor"))?,
+        };
+
+        if w == 0 || h == 0 {
+            return Err(Error::InvalidParam("Canvas size must be positive"));
+        }
+
+        Ok(Action::Canvas(Dimension::from_wh(w, h), anchor))
+    }
+
+    fn parse_grid(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let str_w = match parts.next() {
+            None => return Ok(Action::Grid(GridChange::Toggle)),
+            Some("off") => return Ok(Action::Grid(GridChange::Off)),
+            Some("color") => return Ok(Action::Grid(GridChange::Color)),
+            Some(str_w) => str_w,
+        };
+
+        let w = str_w
+            .parse::<u16>()
+            .map_err(|_| Error::InvalidParam("Invalid width value"))?;
+        let h = parts
+            .next()
+            .ok_or(Error::InvalidParam("No height specified"))?
+            .parse::<u16>()
+            .map_err(|_| Error::InvalidParam("Invalid height value"))?;
+
+        if w == 0 || h == 0 {
+            return Err(Error::InvalidParam("Grid size must be positive"));
+        }
+
+        let mut offset = Position2D::default();
+        if let Some(str_x) = parts.next() {
+            offset.x = str_x
+                .parse::<i32>()
+                .map_err(|_| Error::InvalidParam("Invalid offset X value"))?;
+            offset.y = parts
+                .next()
+                .ok_or(Error::InvalidParam("No offset Y specified"))?
+                .parse::<i32>()
+                .map_err(|_| Error::InvalidParam("Invalid offset Y value"))?;
+        }
+
+        Ok(Action::Grid(GridChange::Resize((w, h), offset)))
+    }
+
+    fn parse_guide(&self, mut parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let vertical = match parts.next() {
+            Some("vertical") | Some("v") => true,
+            Some("horizontal") | Some("h") => false,
+            Some("clear") => return Ok(Action::Guide(GuideChange::Clear)),
+            Some(_) => return Err(Error::InvalidParam("Invalid guide direction")),
+            None => return Err(Error::InvalidParam("No guide direction specified")),
+        };
+
+        let at = match parts.next() {
+            None => None,
+            Some(str_at) => Some(
+                str_at
+                    .parse::<i32>()
+                    .map_err(|_| Error::InvalidParam("Invalid guide position"))?,
+            ),
+        };
+
+        Ok(Action::Guide(GuideChange::Add(vertical, at)))
+    }
+
+    fn parse_play(&self, parts: Peekable<SplitAsciiWhitespace>) -> Result<Action, Error> {
+        let mut playback = Playback::default();
+
+        for part in parts {
+            match part {
+                "loop" => playback.ping_pong = false,
+                "pingpong" => playback.ping_pong = true,
+                "all" => playback.all = true,
+                "selected" => playback.all = false,
+                _ => {
+                    playback.fps = part
+                        .parse::<u16>()
+                        .map_err(|_| Error::InvalidParam("Invalid playback option"))?;
+
+                    if playback.fps == 0 {
+                        return Err(Error::InvalidParam("FPS must be positive"));
+                    }
+                }
+            }
+        }
+
+        Ok(Action::Play(playback))
+    }
 }
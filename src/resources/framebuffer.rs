@@ -1,3 +1,4 @@
+use crate::common::{ColorTable, ExtStyles};
 use crate::os::Terminal;
 use std::io::Write;
 use std::vec::Vec;
@@ -8,6 +9,7 @@ struct TexelBuf {
     size_x: usize,
     size_y: usize,
     buf: Texels,
+    ext: Vec<ExtStyles>, // extended styles of each texel in buf
 }
 
 impl TexelBuf {
@@ -16,6 +18,7 @@ impl TexelBuf {
             size_x,
             size_y,
             buf: Vec::with_capacity(size_x * size_y),
+            ext: Vec::with_capacity(size_x * size_y),
         };
         result.clear();
 
@@ -24,6 +27,7 @@ impl TexelBuf {
 
     pub fn clear(&mut self) {
         self.buf.clear();
+        self.ext.clear();
         for i in 0..self.buf.capacity() {
             let pos = self.deindex(i);
             let texel = Texel {
@@ -35,13 +39,15 @@ impl TexelBuf {
             };
 
             self.buf.push(texel);
+            self.ext.push(ExtStyles::new());
         }
     }
 
-    pub fn set_texel(&mut self, texel: Texel) {
+    pub fn set_texel(&mut self, texel: Texel, ext_styles: ExtStyles) {
         if let Some(index) = self.index(texel.pos) {
             if index < self.buf.len() {
                 self.buf[index] = texel;
+                self.ext[index] = ext_styles;
             }
         }
     }
@@ -55,31 +61,35 @@ impl TexelBuf {
             if let Some(existing) = self.buf.get_mut(index) {
                 existing.bg = texel.bg;
             } else {
-                self.set_texel(texel);
+                self.set_texel(texel, ExtStyles::new());
             }
         }
     }
 
-    fn texel_match(&self, texel: &Texel) -> bool {
+    fn texel_match(&self, texel: &Texel, ext_styles: ExtStyles) -> bool {
         if let Some(index) = self.index(texel.pos) {
-            self.buf[index] == *texel
+            self.buf[index] == *texel && self.ext[index] == ext_styles
         } else {
             false
         }
     }
 
-    pub fn diff(newer: &Self, older: &Self) -> Texels {
+    pub fn diff(newer: &Self, older: &Self) -> Vec<(Texel, ExtStyles)> {
         let mut vec = Vec::with_capacity(newer.buf.capacity());
 
-        for texel in &newer.buf {
-            if !older.texel_match(texel) {
-                vec.push(texel.clone());
+        for (texel, ext_styles) in newer.buf.iter().zip(newer.ext.iter()) {
+            if !older.texel_match(texel, *ext_styles) {
+                vec.push((texel.clone(), *ext_styles));
             }
         }
 
         vec
     }
 
+    pub fn all(&self) -> Vec<(Texel, ExtStyles)> {
+        self.buf.iter().cloned().zip(self.ext.iter().copied()).collect()
+    }
+
     fn index(&self, pos: Position2D) -> Option<usize> {
         if pos.y < 0 || pos.x < 0 {
             None
@@ -102,6 +112,9 @@ pub struct FrameBuffer {
     index: usize,
     cursor_x: i32,
     cursor_y: i32,
+    colors: ColorTable, // scene colors
+    truecolor: bool,
+    redraw: bool,
 }
 
 impl FrameBuffer {
@@ -111,6 +124,17 @@ impl FrameBuffer {
             index: 0,
             cursor_x: 1,
             cursor_y: 1,
+            colors: ColorTable::default(),
+            truecolor: Terminal::truecolor(),
+            redraw: false,
+        }
+    }
+
+    // changed colors need a full redraw since texels stay the same
+    pub fn set_colors(&mut self, colors: &ColorTable) {
+        if &self.colors != colors {
+            self.colors = colors.clone();
+            self.redraw = true;
         }
     }
 
@@ -122,13 +146,17 @@ impl FrameBuffer {
     }
 
     pub fn write_texel(&mut self, texel: Texel) {
+        self.write_styled_texel(texel, ExtStyles::new());
+    }
+
+    pub fn write_styled_texel(&mut self, texel: Texel, ext_styles: ExtStyles) {
         if !Self::is_visible(texel.pos.x, texel.pos.y) {
             return;
         }
 
         let buf = self.buf_mut();
 
-        buf.set_texel(texel);
+        buf.set_texel(texel, ext_styles);
     }
 
     pub fn write_texels(&mut self, texels: Texels) {
@@ -154,8 +182,7 @@ impl FrameBuffer {
     ) {
         let text = format!("{}", source);
 
-        let mut x: i32 = start_x;
-        for symbol in text.chars() {
+        for (x, symbol) in (start_x..).zip(text.chars()) {
             self.write_texel(Texel {
                 pos: Position2D { x, y },
                 symbol,
@@ -163,8 +190,6 @@ impl FrameBuffer {
                 bg,
                 styles,
             });
-
-            x += 1;
         }
     }
 
@@ -192,13 +217,18 @@ impl FrameBuffer {
         self.buffers = [TexelBuf::new(size_x, size_y), TexelBuf::new(size_x, size_y)];
     }
 
-    pub fn flush_into(&self, out: &mut dyn Write) -> Result<(), std::io::Error> {
+    pub fn flush_into(&mut self, out: &mut dyn Write) -> Result<(), std::io::Error> {
         use crate::common::TexelExt;
 
-        let vec = TexelBuf::diff(self.buf(), self.previous_buf());
+        let vec = if self.redraw {
+            self.buf().all()
+        } else {
+            TexelBuf::diff(self.buf(), self.previous_buf())
+        };
+        self.redraw = false;
 
-        for texel in vec {
-            write!(out, "{}", texel.to_string())?;
+        for (texel, ext_styles) in vec {
+            write!(out, "{}", texel.to_string(&self.colors, ext_styles, self.truecolor))?;
         }
 
         write!(out, "{}", Terminal::goto(self.cursor_x, self.cursor_y))?;
@@ -1,5 +1,5 @@
 mod color_palette;
 mod symbol_palette;
 
-pub use color_palette::{ColorPalette, MAX_COLOR_INDEX, PALETTE_H, PALETTE_OFFSET, PALETTE_W};
+pub use color_palette::{ColorPalette, PALETTE_H, PALETTE_OFFSET, PALETTE_W};
 pub use symbol_palette::SymbolPalette;
@@ -1,17 +1,22 @@
-use crate::common::Error;
+use crate::common::{Error, Rgb};
 use crate::components::Position2D;
 use crate::os::Terminal;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use texel_types::{ColorMode, SymbolStyle, SymbolStyles, Texel, Texels};
 
 const fn cc(r: u8, g: u8, b: u8) -> u8 {
     16 + 36 * r + 6 * g + b
 }
 
-pub const PALETTE_W: i32 = 16;
-pub const PALETTE_H: i32 = 14;
+// picker layout: system colors, 6 rows of the 6x6x6 cube and the grayscale ramp
+pub const PALETTE_W: i32 = 36;
+pub const PALETTE_H: i32 = 9; // includes the cmdline row
 pub const PALETTE_OFFSET: i32 = 18;
-pub const MAX_COLOR_INDEX: u8 = 6 * 6 * 6;
+
+const PICKER_CUBE_ROW: i32 = 1;
+const PICKER_GRAY_ROW: i32 = 7;
+const PICKER_SECTION_ROWS: [i32; 3] = [0, PICKER_CUBE_ROW, PICKER_GRAY_ROW];
 
 const COLORS_IN_PALETTE: usize = 16;
 
@@ -38,22 +43,74 @@ const COLOR_SELECTOR: [char; COLORS_IN_PALETTE] = [
     '1', '2', '3', '4', '5', '6', '7', '8', '9', '0', 'a', 'b', 'c', 'd', 'e', 'f',
 ];
 
+// extra pages, grays, darks and lights
+const DEFAULT_EXTRA_PAGES: [[u8; COLORS_IN_PALETTE]; 3] = [
+    [
+        232, 233, 235, 236, 238, 239, 241, 242, 244, 245, 247, 248, 250, 251, 253, 255,
+    ],
+    [
+        cc(1, 0, 0),
+        cc(0, 1, 0),
+        cc(0, 0, 1),
+        cc(1, 1, 0),
+        cc(0, 1, 1),
+        cc(1, 0, 1),
+        cc(2, 0, 0),
+        cc(0, 2, 0),
+        cc(0, 0, 2),
+        cc(2, 2, 0),
+        cc(0, 2, 2),
+        cc(2, 0, 2),
+        cc(2, 1, 0),
+        cc(1, 2, 0),
+        cc(0, 1, 2),
+        cc(2, 1, 1),
+    ],
+    [
+        cc(5, 4, 3),
+        cc(5, 3, 1),
+        cc(4, 2, 0),
+        cc(3, 1, 0),
+        cc(5, 4, 4),
+        cc(4, 3, 2),
+        cc(3, 2, 1),
+        cc(2, 1, 0),
+        cc(4, 5, 3),
+        cc(3, 4, 2),
+        cc(3, 4, 5),
+        cc(2, 3, 4),
+        cc(4, 4, 5),
+        cc(5, 5, 4),
+        cc(4, 3, 5),
+        cc(5, 3, 4),
+    ],
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ColorPalette {
-    colors: [u8; COLORS_IN_PALETTE],
+    colors: [u8; COLORS_IN_PALETTE], // first page
+    #[serde(default)]
+    rgb: BTreeMap<usize, Rgb>, // exact colors of entries across pages, colors hold nearest ANSI value
+    #[serde(default = "default_pages")]
+    pages: Vec<[u8; COLORS_IN_PALETTE]>, // pages after the first one
+    #[serde(default)]
+    page: usize, // active page
 }
 
 impl Default for ColorPalette {
     fn default() -> Self {
-        Self {
-            colors: DEFAULT_PALETTE_COLORS,
-        }
+        Self::from(DEFAULT_PALETTE_COLORS)
     }
 }
 
 impl From<[u8; COLORS_IN_PALETTE]> for ColorPalette {
     fn from(colors: [u8; COLORS_IN_PALETTE]) -> Self {
-        ColorPalette { colors }
+        ColorPalette {
+            colors,
+            rgb: BTreeMap::new(),
+            pages: default_pages(),
+            page: 0,
+        }
     }
 }
 
@@ -65,56 +122,218 @@ impl From<&[u8]> for ColorPalette {
             palette_colors[i] = *color;
         }
 
-        ColorPalette { colors: palette_colors }
+        Self::from(palette_colors)
     }
 }
 
+fn default_pages() -> Vec<[u8; COLORS_IN_PALETTE]> {
+    DEFAULT_EXTRA_PAGES.to_vec()
+}
+
 impl ColorPalette {
     pub fn color(&self, index: usize) -> u8 {
-        self.colors[index]
+        self.active()[index]
     }
 
     pub fn set_color(&mut self, index: usize, color: u8) -> Result<(), Error> {
-        if index >= self.colors.len() {
+        if index >= COLORS_IN_PALETTE {
             return Err(Error::execution("Index out of bounds"));
         }
 
-        self.colors[index] = color;
+        let key = self.key(index);
+        self.active_mut()[index] = color;
+        self.rgb.remove(&key);
+
+        Ok(())
+    }
+
+    // color indexes on all pages
+    pub fn used_colors(&self) -> impl Iterator<Item = u8> + '_ {
+        (0..self.page_count()).flat_map(move |page| self.page_colors(page).iter().copied())
+    }
+
+    pub fn rgb(&self, index: usize) -> Option<Rgb> {
+        self.rgb.get(&self.key(index)).copied()
+    }
+
+    pub fn set_rgb(&mut self, index: usize, rgb: Rgb) -> Result<(), Error> {
+        self.set_color(index, rgb.nearest_ansi())?;
+        self.rgb.insert(self.key(index), rgb);
+
+        Ok(())
+    }
+
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len() + 1
+    }
+
+    pub fn switch_page(&mut self, forward: bool) {
+        let count = self.page_count();
+
+        self.page = if forward {
+            (self.page + 1) % count
+        } else {
+            (self.page + count - 1) % count
+        };
+    }
+
+    // exact colors of all entries on all pages
+    pub fn rgb_colors(&self) -> Vec<Rgb> {
+        (0..self.page_count())
+            .flat_map(|page| (0..COLORS_IN_PALETTE).map(move |i| (page, i)))
+            .map(|(page, i)| {
+                let key = page * COLORS_IN_PALETTE + i;
+                let color = self.page_colors(page)[i];
+
+                self.rgb.get(&key).copied().unwrap_or_else(|| Rgb::from_ansi(color))
+            })
+            .collect()
+    }
+
+    // replaces entries from the start of the first page, adds pages as needed
+    pub fn set_rgb_colors(&mut self, colors: &[Rgb]) -> Result<(), Error> {
+        let active = self.page;
+
+        for (i, rgb) in colors.iter().enumerate() {
+            self.page = i / COLORS_IN_PALETTE;
+            if self.page >= self.page_count() {
+                self.pages.push([texel_types::DEFAULT_BG_U8; COLORS_IN_PALETTE]);
+            }
+            self.set_rgb(i % COLORS_IN_PALETTE, *rgb)?;
+        }
+        self.page = std::cmp::min(active, self.page_count() - 1);
 
         Ok(())
     }
 
+    fn key(&self, index: usize) -> usize {
+        self.page * COLORS_IN_PALETTE + index
+    }
+
+    fn page_colors(&self, page: usize) -> &[u8; COLORS_IN_PALETTE] {
+        match page {
+            0 => &self.colors,
+            _ => self.pages.get(page - 1).unwrap_or(&self.colors),
+        }
+    }
+
+    fn active(&self) -> &[u8; COLORS_IN_PALETTE] {
+        self.page_colors(self.page)
+    }
+
+    fn active_mut(&mut self) -> &mut [u8; COLORS_IN_PALETTE] {
+        match self.page {
+            0 => &mut self.colors,
+            page => match self.pages.get_mut(page - 1) {
+                Some(colors) => colors,
+                None => &mut self.colors,
+            },
+        }
+    }
+
     pub fn subselection_bg_u8() -> u8 {
         Terminal::grayscale_u8(10)
     }
 
-    pub fn pos_to_color(pos: Position2D) -> u8 {
+    // darker shade of given color, used for drop shadows
+    pub fn darken(color: u8) -> u8 {
+        match color {
+            0..=7 => cc(0, 0, 0),
+            8..=15 => color - 8,
+            16..=231 => {
+                let base = color - 16;
+                let (r, g, b) = (base / 36, (base / 6) % 6, base % 6);
+                cc(r / 2, g / 2, b / 2)
+            }
+            _ => Terminal::grayscale_u8((color - Terminal::grayscale_u8(0)) / 2),
+        }
+    }
+
+    // top left corner of the color picker on screen
+    pub fn picker_pos() -> Position2D {
         let ts = Terminal::terminal_size();
-        let min = Position2D {
+
+        Position2D {
             x: PALETTE_OFFSET,
             y: i32::from(ts.1) - PALETTE_H,
-        };
-        let mut base = Self::pos_to_base(pos - min);
+        }
+    }
+
+    // screen position to color, None for the empty cells of the picker
+    pub fn pos_to_color(pos: Position2D) -> Option<u8> {
+        Self::picker_color(pos - Self::picker_pos())
+    }
 
-        if base >= MAX_COLOR_INDEX {
-            base = 0; // black on black
+    pub fn color_to_pos(color: u8) -> Position2D {
+        Self::picker_pos() + Self::picker_cell(color)
+    }
+
+    // color at given picker cell, relative to the picker's top left corner
+    pub fn picker_color(cell: Position2D) -> Option<u8> {
+        let (x, y) = (cell.x, cell.y);
+
+        if !(0..PALETTE_W).contains(&x) {
+            return None;
         }
 
-        base + 16
+        match y {
+            0 if x < 16 => Some(x as u8),
+            PICKER_CUBE_ROW..=6 => {
+                let (r, g, b) = (x / 6, y - PICKER_CUBE_ROW, x % 6);
+                Some(cc(r as u8, g as u8, b as u8))
+            }
+            PICKER_GRAY_ROW if x < 24 => Some(Terminal::grayscale_u8(x as u8)),
+            _ => None,
+        }
     }
 
-    pub const fn base_to_rgb(base: u8) -> (u8, u8, u8) {
-        (base / 36, (base / 6) % 6, base % 6)
+    pub fn picker_cell(color: u8) -> Position2D {
+        match color {
+            0..=15 => Position2D {
+                x: i32::from(color),
+                y: 0,
+            },
+            16..=231 => {
+                let base = color - 16;
+                let (r, g, b) = (base / 36, (base / 6) % 6, base % 6);
+                Position2D {
+                    x: i32::from(r * 6 + b),
+                    y: PICKER_CUBE_ROW + i32::from(g),
+                }
+            }
+            _ => Position2D {
+                x: i32::from(color - Terminal::grayscale_u8(0)),
+                y: PICKER_GRAY_ROW,
+            },
+        }
     }
 
-    pub const fn pos_to_base(pos: Position2D) -> u8 {
-        (pos.y * PALETTE_W) as u8 + pos.x as u8
+    // start of the next/previous picker section (system, cube, grays) from given screen position
+    pub fn picker_section(pos: Position2D, forward: bool) -> Position2D {
+        let min = Self::picker_pos();
+        let row = pos.y - min.y;
+        let count = PICKER_SECTION_ROWS.len();
+        let current = PICKER_SECTION_ROWS.iter().rposition(|r| *r <= row).unwrap_or_default();
+        let next = if forward {
+            (current + 1) % count
+        } else {
+            (current + count - 1) % count
+        };
+
+        Position2D {
+            x: min.x,
+            y: min.y + PICKER_SECTION_ROWS[next],
+        }
     }
 
     pub fn selector_texel(&self, index: usize, pos: Position2D, cm: ColorMode) -> Texel {
         let (bg, fg) = match cm {
-            ColorMode::Bg => (self.colors[index], invert_luminance(self.colors[index])),
-            ColorMode::Fg => (invert_luminance(self.colors[index]), self.colors[index]),
+            ColorMode::Bg => (self.active()[index], invert_luminance(self.active()[index])),
+            ColorMode::Fg => (invert_luminance(self.active()[index]), self.active()[index]),
         };
 
         let u8_val = crate::common::index_from_one(index) as u8;
@@ -141,11 +360,10 @@ impl ColorPalette {
     pub fn line_texels(&self, start_x: i32, y: i32, cm: ColorMode) -> Texels {
         let mut result = Vec::with_capacity(COLORS_IN_PALETTE);
 
-        let mut x = start_x;
-        for (i, symbol) in COLOR_SELECTOR.iter().enumerate() {
+        for (i, (x, symbol)) in (start_x..).zip(COLOR_SELECTOR.iter()).enumerate() {
             let (bg, fg) = match cm {
-                ColorMode::Bg => (self.colors[i], invert_luminance(self.colors[i])),
-                ColorMode::Fg => (invert_luminance(self.colors[i]), self.colors[i]),
+                ColorMode::Bg => (self.active()[i], invert_luminance(self.active()[i])),
+                ColorMode::Fg => (invert_luminance(self.active()[i]), self.active()[i]),
             };
             let pos = Position2D { x, y };
 
@@ -156,28 +374,43 @@ impl ColorPalette {
                 symbol: *symbol,
                 styles: SymbolStyles::only(SymbolStyle::Bold),
             });
-            x += 1;
         }
 
         result
     }
 }
 
-fn re_rgb(color: u8) -> (u8, u8, u8) {
-    let base = color - 16;
-    (base / 36, (base / 6) % 6, base % 6)
-}
-
 fn luminance(color: u8) -> u8 {
-    let (r, g, b) = re_rgb(color);
-    // get luminance according to spec (output is in 0..6 tho same as ansivalue bases)
-    (0.2126 * f32::from(r) + 0.7151 * f32::from(g) + 0.0721 * f32::from(b)) as u8
+    let rgb = Rgb::from_ansi(color);
+    // get luminance according to spec, covers system colors and grays too
+    (0.2126 * f32::from(rgb.r) + 0.7152 * f32::from(rgb.g) + 0.0722 * f32::from(rgb.b)) as u8
 }
 
 fn invert_luminance(color: u8) -> u8 {
-    if luminance(color) >= 2 {
+    if luminance(color) >= 128 {
         Terminal::grayscale_u8(5)
     } else {
         Terminal::grayscale_u8(17)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_system_and_gray_colors() {
+        let mut palette = ColorPalette::default();
+        palette.set_color(0, 3).unwrap(); // system olive
+        palette.set_color(1, 15).unwrap(); // system white
+        palette.set_color(2, Terminal::grayscale_u8(2)).unwrap();
+
+        let texels = palette.line_texels(0, 0, ColorMode::Bg);
+        assert_eq!(texels[0].bg, 3);
+        assert_eq!(texels[1].fg, Terminal::grayscale_u8(5)); // dark text on white
+        assert_eq!(texels[2].fg, Terminal::grayscale_u8(17)); // light text on dark gray
+
+        let selector = palette.selector_texel(0, Position2D::default(), ColorMode::Fg);
+        assert_eq!(selector.fg, 3);
+    }
+}
@@ -7,22 +7,41 @@ const DEFAULT_SYMBOLS: [char; SYMBOLS_IN_PALETTE] = [
     '|', '-', '_', '=', '\\', '/', '[', ']', '~', 'O', '*', '^', '#', '@', '!', '?',
 ];
 
+// extra pages, shades and blocks, box drawing, lines and shapes
+const DEFAULT_EXTRA_PAGES: [[char; SYMBOLS_IN_PALETTE]; 3] = [
+    [
+        '░', '▒', '▓', '█', '▀', '▄', '▌', '▐', '▖', '▗', '▘', '▝', '▚', '▞', '■', '·',
+    ],
+    [
+        '─', '│', '┌', '┐', '└', '┘', '├', '┤', '┬', '┴', '┼', '═', '║', '╔', '╗', '╚',
+    ],
+    [
+        '╝', '╠', '╣', '╦', '╩', '╬', '╭', '╮', '╯', '╰', '╱', '╲', '╳', '●', '○', '◆',
+    ],
+];
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SymbolPalette {
-    symbols: [char; SYMBOLS_IN_PALETTE],
+    symbols: [char; SYMBOLS_IN_PALETTE], // first page
+    #[serde(default = "default_pages")]
+    pages: Vec<[char; SYMBOLS_IN_PALETTE]>, // pages after the first one
+    #[serde(default)]
+    page: usize,    // active page
 }
 
 impl Default for SymbolPalette {
     fn default() -> Self {
-        SymbolPalette {
-            symbols: DEFAULT_SYMBOLS,
-        }
+        Self::from(DEFAULT_SYMBOLS)
     }
 }
 
 impl From<[char; SYMBOLS_IN_PALETTE]> for SymbolPalette {
     fn from(symbols: [char; SYMBOLS_IN_PALETTE]) -> Self {
-        SymbolPalette { symbols }
+        SymbolPalette {
+            symbols,
+            pages: default_pages(),
+            page: 0,
+        }
     }
 }
 
@@ -34,30 +53,69 @@ impl From<&[char]> for SymbolPalette {
             symbol_chars[i] = *symbol;
         }
 
-        SymbolPalette { symbols: symbol_chars }
+        Self::from(symbol_chars)
     }
 }
 
+fn default_pages() -> Vec<[char; SYMBOLS_IN_PALETTE]> {
+    DEFAULT_EXTRA_PAGES.to_vec()
+}
+
 impl SymbolPalette {
     pub fn symbol(&self, index: usize) -> char {
-        self.symbols[index]
+        self.active()[index]
     }
 
     pub fn set_symbol(&mut self, index: usize, symbol: char) -> Result<(), Error> {
-        if index >= self.symbols.len() {
+        if index >= SYMBOLS_IN_PALETTE {
             return Err(Error::execution("Symbol index out of bounds"));
         }
 
-        self.symbols[index] = symbol;
+        self.active_mut()[index] = symbol;
 
         Ok(())
     }
 
+    pub fn page(&self) -> usize {
+        self.page
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.len() + 1
+    }
+
+    pub fn switch_page(&mut self, forward: bool) {
+        let count = self.page_count();
+
+        self.page = if forward {
+            (self.page + 1) % count
+        } else {
+            (self.page + count - 1) % count
+        };
+    }
+
+    fn active(&self) -> &[char; SYMBOLS_IN_PALETTE] {
+        match self.page {
+            0 => &self.symbols,
+            page => self.pages.get(page - 1).unwrap_or(&self.symbols),
+        }
+    }
+
+    fn active_mut(&mut self) -> &mut [char; SYMBOLS_IN_PALETTE] {
+        match self.page {
+            0 => &mut self.symbols,
+            page => match self.pages.get_mut(page - 1) {
+                Some(symbols) => symbols,
+                None => &mut self.symbols,
+            },
+        }
+    }
+
     pub fn line_texels(&self, start_x: i32, y: i32) -> Texels {
         let mut result = Vec::with_capacity(SYMBOLS_IN_PALETTE * 4);
 
         let mut x = start_x;
-        for symbol in self.symbols.iter() {
+        for symbol in self.active().iter() {
             result.push(Texel {
                 pos: Position2D { x, y },
                 symbol: *symbol,
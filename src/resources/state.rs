@@ -1,34 +1,15 @@
-use crate::common::{Action, Clipboard, Error, InputEvent, Mode, Scene};
+use crate::common::{
+    Action, Autosave, Clipboard, ColorDepth, ColorTable, Error, Grid, Guides, InputEvent, MagicWand, Mode, OnionSkin,
+    Playback, Rgb, Scene, Symmetry,
+};
 use crate::components::Position2D;
-use std::collections::VecDeque;
+use std::collections::{BTreeSet, VecDeque};
+use std::time::{Duration, Instant};
 use texel_types::ColorMode;
 
-const HISTORY_CAPACITY: usize = 20usize;
+mod history;
 
-// snapshot of editor state
-#[derive(Debug, Default, Clone)]
-pub struct Snapshot {
-    pub scene: Scene,
-    pub selections: Vec<usize>,
-}
-
-impl From<Scene> for Snapshot {
-    fn from(scene: Scene) -> Self {
-        Snapshot {
-            scene,
-            selections: Vec::new(),
-        }
-    }
-}
-
-impl From<(Scene, Vec<usize>)> for Snapshot {
-    fn from(data: (Scene, Vec<usize>)) -> Self {
-        Snapshot {
-            scene: data.0,
-            selections: data.1,
-        }
-    }
-}
+pub use history::{History, HistoryItem, Snapshot};
 
 // #[derive(Debug)]
 pub struct State {
@@ -37,17 +18,34 @@ pub struct State {
     events: VecDeque<InputEvent>, // (raw, Option<mapping>)
     actions: VecDeque<Action>,
     modes: VecDeque<Mode>,
-    history: VecDeque<Snapshot>, // scene + list of selected indexes
-    history_index: usize,
+    history: History, // scene + list of selected indexes, deltas for undo/redo
     selected_color: (u8, u8),
     save_state: (Option<String>, usize, usize), // save file path, changes, change "start" index
+    swap_state: (usize, Option<Instant>),       // changes at last swap, time of first change since
+    stale_swap: bool,                           // earlier session's swap not recovered or discarded yet
+    unsaved_settings: bool,                     // scene settings outside of history changed since save
+    symmetry: (bool, Symmetry),                 // mirrored drawing enabled + last used setup
+    onion_skin: (bool, OnionSkin),              // onion skin enabled + last used setup
     // TODO: refactor these off?
-    offset: Position2D, // viewport "offset"
+    offset: Position2D,      // viewport "offset"
+    dirtied_by: Vec<String>, // names of actions since last history push
     pub dirty: bool,
     pub clipboard: Clipboard,
     pub cursor: Position2D,
     pub mouse_entry: Position2D, // previous mouse position for dragging
     pub show_meta: bool,
+    pub wand: MagicWand,
+    pub entered_group: Option<usize>, // group whose children are being edited
+    pub grid: Grid,
+    pub guides: Guides,
+    pub rulers: bool,
+    pub crosshair: bool,
+    pub playback: Playback,
+    pub timeline_tick: usize,           // scrubbed scene timeline position
+    pub colors: ColorTable,             // exact scene colors
+    pub preview: Option<ColorDepth>,    // renders the scene in reduced colors
+    pub persistent_undo: Option<usize>, // bytes of undo history saved next to scenes, off if none
+    pub autosave: Autosave,             // swap file triggers
 }
 
 impl Default for State {
@@ -58,21 +56,37 @@ impl Default for State {
             events: VecDeque::with_capacity(10),
             actions: VecDeque::with_capacity(10),
             modes: VecDeque::with_capacity(5),
-            history: VecDeque::with_capacity(HISTORY_CAPACITY),
-            history_index: 0usize,
+            history: History::default(),
             selected_color: (texel_types::DEFAULT_BG_U8, texel_types::DEFAULT_FG_U8),
             save_state: (None, 0, 0),
+            swap_state: (0, None),
+            stale_swap: false,
+            unsaved_settings: false,
+            symmetry: (false, Symmetry::default()),
+            onion_skin: (false, OnionSkin::default()),
             // others
+            dirtied_by: Vec::new(),
             dirty: false,
             clipboard: Clipboard::Empty,
             cursor: Position2D::default(),
             offset: Position2D::default(),
             mouse_entry: Position2D::default(),
             show_meta: false,
+            wand: MagicWand::default(),
+            entered_group: None,
+            grid: Grid::default(),
+            guides: Guides::default(),
+            rulers: false,
+            crosshair: false,
+            playback: Playback::default(),
+            timeline_tick: 0,
+            colors: ColorTable::default(),
+            preview: None,
+            persistent_undo: None,
+            autosave: Autosave::default(),
         };
 
         result.modes.push_back(Mode::default()); // there is always a mode!
-        result.history.push_back(Snapshot::default()); // empty
 
         result
     }
@@ -97,12 +111,67 @@ impl State {
         }
     }
 
+    // assigns exact color an index in scene color table and selects it
+    pub fn set_rgb_color(&mut self, rgb: Rgb, cm: ColorMode, used: &BTreeSet<u8>) -> Result<(), Error> {
+        let index = self.colors.assign(rgb, used)?;
+        self.set_color(index, cm);
+
+        Ok(())
+    }
+
     pub fn swap_color(&mut self) -> bool {
         std::mem::swap(&mut self.selected_color.0, &mut self.selected_color.1);
 
         false
     }
 
+    // mirrored drawing only applies to edit/write modes
+    pub fn symmetry(&self) -> Option<Symmetry> {
+        if self.symmetry.0 && self.mode().modifies_cursor() {
+            Some(self.symmetry.1)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_symmetry(&mut self, symmetry: Option<Symmetry>) -> bool {
+        match symmetry {
+            Some(sym) => self.symmetry = (true, sym),
+            None => self.symmetry.0 = false,
+        }
+
+        false
+    }
+
+    pub fn toggle_symmetry(&mut self) -> bool {
+        self.symmetry.0 = !self.symmetry.0;
+
+        false
+    }
+
+    pub fn onion_skin(&self) -> Option<OnionSkin> {
+        if self.onion_skin.0 && self.mode().modifies_cursor() {
+            Some(self.onion_skin.1)
+        } else {
+            None
+        }
+    }
+
+    pub fn set_onion_skin(&mut self, onion_skin: Option<OnionSkin>) -> bool {
+        match onion_skin {
+            Some(onion) => self.onion_skin = (true, onion),
+            None => self.onion_skin.0 = false,
+        }
+
+        false
+    }
+
+    pub fn toggle_onion_skin(&mut self) -> bool {
+        self.onion_skin.0 = !self.onion_skin.0;
+
+        false
+    }
+
     // hacky way to keep help + command_during_help from offsetting the viewport
     fn offset_for_mode(&self, mode: Mode) -> Position2D {
         match mode {
@@ -147,7 +216,13 @@ impl State {
     }
 
     pub fn unsaved_changes(&self) -> bool {
-        self.save_state.1 > 0 && self.history_index != self.save_state.2
+        self.unsaved_settings || (self.save_state.1 > 0 && self.history.index() != self.save_state.2)
+    }
+
+    // grid and guides are saved with the scene but not part of history
+    pub fn settings_changed(&mut self) {
+        self.unsaved_settings = true;
+        self.changed();
     }
 
     pub fn set_mode(&mut self, mode: Mode) -> bool {
@@ -181,6 +256,22 @@ impl State {
         }
     }
 
+    // selected state id of the undo tree view
+    pub fn show_history(&self) -> Option<usize> {
+        if let Mode::History(id) = self.mode() {
+            Some(id)
+        } else if let (Mode::Command, Some(Mode::History(id))) = (self.mode(), self.previous_mode()) {
+            Some(id)
+        } else {
+            None
+        }
+    }
+
+    // help and undo tree view are shown instead of the scene
+    pub fn hides_scene(&self) -> bool {
+        self.show_help().is_some() || self.show_history().is_some()
+    }
+
     pub fn show_help(&self) -> Option<usize> {
         if self.modes.len() > 1 {
             if let Mode::Help(index) = self.mode() {
@@ -212,24 +303,66 @@ impl State {
     }
 
     pub fn saved(&mut self, path: String) -> bool {
-        self.save_state = (Some(path), 0, self.history_index);
+        self.save_state = (Some(path), 0, self.history.index());
+        self.swap_state = (0, None);
+        self.unsaved_settings = false;
 
         false
     }
 
     pub fn clear_changes(&mut self) -> bool {
         self.save_state.1 = 0; // keep filename
-        self.save_state.2 = self.history_index;
+        self.save_state.2 = self.history.index();
+        self.swap_state = (0, None);
+        self.unsaved_settings = false;
 
         false
     }
 
     pub fn reset_save_file(&mut self) -> bool {
         self.save_state = (None, 0, 0);
+        self.swap_state = (0, None);
+        self.unsaved_settings = false;
 
         false
     }
 
+    // time left until changes since last save or swap should be swapped,
+    // never while a stale swap would get overwritten
+    pub fn swap_due_in(&self) -> Option<Duration> {
+        if self.stale_swap {
+            return None;
+        }
+
+        let pending = self.save_state.1.saturating_sub(self.swap_state.0);
+
+        self.autosave.due_in(pending, self.swap_state.1)
+    }
+
+    pub fn swap_due(&self) -> bool {
+        self.swap_due_in() == Some(Duration::from_secs(0))
+    }
+
+    pub fn swapped(&mut self) {
+        self.swap_state = (self.save_state.1, None);
+    }
+
+    pub fn stale_swap(&self) -> bool {
+        self.stale_swap
+    }
+
+    pub fn set_stale_swap(&mut self, stale: bool) {
+        self.stale_swap = stale;
+    }
+
+    fn changed(&mut self) {
+        self.save_state.1 += 1;
+
+        if self.swap_state.1.is_none() {
+            self.swap_state.1 = Some(Instant::now());
+        }
+    }
+
     pub fn quitting(&self) -> bool {
         matches!(self.mode(), Mode::Quitting(_))
     }
@@ -255,77 +388,107 @@ impl State {
     }
 
     // returns if we need to preserve anything
-    // this includes non-dirty selection updates if history.len() == 1
+    // this includes non-dirty selection updates in initial history
     pub fn needs_preserving(&self) -> bool {
-        self.dirty || self.history.len() == 1
+        self.dirty || self.history.is_initial()
+    }
+
+    // marks changes to preserve, action name describes them in history
+    pub fn set_dirty(&mut self, action: String) {
+        if !self.dirtied_by.contains(&action) {
+            self.dirtied_by.push(action);
+        }
+
+        self.dirty = true;
     }
 
     // resets history to start with this scene
     pub fn clear_history(&mut self, scene: Scene) {
-        self.history.clear();
-        self.history.push_back(Snapshot::from(scene));
-        self.history_index = 0;
+        self.history.clear(Snapshot::from(scene));
+        self.dirtied_by.clear();
         self.dirty = false;
     }
 
-    // the minimalist in me screams at this, but doing a delta with
-    // multi-selections and absolute location selections (mouse) is a
-    // major PITA with ECS context, saving the whole thing for undo/redo is
-    // just so much easier to do. Given it's ascii we're only taking kilobytes of
-    // space under typical usage
+    // copy of history within persistent budget, for saving next to the scene
+    pub fn history_to_save(&self) -> Option<History> {
+        self.persistent_undo.map(|budget| self.history.trimmed(budget))
+    }
+
+    // continues saved history if it ends in the freshly loaded scene
+    pub fn restore_history(&mut self, saved: History) {
+        if saved.same_scene(&self.history) {
+            let budget = self.history.budget();
+            self.history = saved;
+            self.history.set_budget(budget);
+        }
+    }
+
+    // undo tree states in display order
+    pub fn history_list(&self) -> Vec<HistoryItem> {
+        self.history.list()
+    }
+
+    pub fn history_colors(&self) -> BTreeSet<u8> {
+        self.history.used_colors()
+    }
+
+    pub fn history_index(&self) -> usize {
+        self.history.index()
+    }
+
+    // bytes of undo/redo deltas to keep, oldest steps are dropped past it
+    pub fn set_history_budget(&mut self, budget: usize) {
+        self.history.set_budget(budget);
+    }
+
+    // history keeps the current scene whole and per object deltas
+    // (added, removed and changed components) to reach other states of the tree
     pub fn push_history(&mut self, scene: Scene, selections: Vec<usize>) {
         if !self.dirty {
             // non-dirtying change in initial setup, refresh selections
-            if self.history.len() == 1 && !selections.is_empty() {
-                self.history[0].scene = scene; // ordering can change, preserve it
-                self.history[0].selections = selections;
+            if self.history.is_initial() && !selections.is_empty() {
+                // ordering can change, preserve it
+                self.history.refresh(Snapshot::from((scene, selections)));
             }
 
             return;
         }
 
-        if self.history.len() >= HISTORY_CAPACITY {
-            self.history.pop_front();
-        }
-
-        if !self.history.is_empty() && self.history_index != self.history.len() - 1 {
-            self.history.truncate(self.history_index + 1);
-        }
-
-        self.history.push_back(Snapshot::from((scene, selections)));
-        let next_index = self.history.len() - 1;
-
-        self.history_index = next_index;
+        let label = self.dirtied_by.join(", ");
+        self.history.push(Snapshot::from((scene, selections)), label);
+        self.dirtied_by.clear();
         self.dirty = false;
 
-        self.save_state.1 += 1;
+        self.changed();
     }
 
     pub fn undo(&mut self) -> Option<Snapshot> {
-        if self.history_index == 0 {
-            return None;
-        }
+        let result = self.history.undo();
 
-        self.history_index -= 1;
-        if let Some(value) = self.history.get(self.history_index) {
-            self.save_state.1 += 1;
-            return Some(value.clone());
+        if result.is_some() {
+            self.changed();
         }
 
-        None
+        result
     }
 
     pub fn redo(&mut self) -> Option<Snapshot> {
-        if self.history.is_empty() || self.history_index >= self.history.len() - 1 {
-            return None;
+        let result = self.history.redo();
+
+        if result.is_some() {
+            self.changed();
         }
 
-        self.history_index += 1;
-        if let Some(value) = self.history.get(self.history_index) {
-            self.save_state.1 += 1;
-            return Some(value.clone());
+        result
+    }
+
+    pub fn jump_history(&mut self, id: usize) -> Option<Snapshot> {
+        let result = self.history.jump(id);
+
+        if result.is_some() {
+            self.changed();
         }
 
-        None
+        result
     }
 }
@@ -0,0 +1,523 @@
+use crate::common::{ObjectInfo, Scene, SceneV3};
+use crate::components::{Position, Position2D, Sprite};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::SystemTime;
+use texel_types::Texel;
+
+const DEFAULT_HISTORY_BUDGET: usize = 16 * 1024 * 1024; // bytes
+
+type Object = (Sprite, Position, ObjectInfo);
+type Bookmarks = BTreeMap<usize, Position2D>;
+type Groups = BTreeMap<usize, Option<usize>>; // group id -> parent group id
+
+// snapshot of editor state
+#[derive(Debug, Default, Clone)]
+pub struct Snapshot {
+    pub scene: Scene,
+    pub selections: Vec<usize>,
+}
+
+impl From<Scene> for Snapshot {
+    fn from(scene: Scene) -> Self {
+        Snapshot {
+            scene,
+            selections: Vec::new(),
+        }
+    }
+}
+
+impl From<(Scene, Vec<usize>)> for Snapshot {
+    fn from(data: (Scene, Vec<usize>)) -> Self {
+        Snapshot {
+            scene: data.0,
+            selections: data.1,
+        }
+    }
+}
+
+// changed components of a single object, older and newer value
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ObjectDelta {
+    sprite: Option<(Sprite, Sprite)>,
+    position: Option<(Position, Position)>,
+    info: Option<(ObjectInfo, ObjectInfo)>,
+}
+
+impl ObjectDelta {
+    fn between(older: &Object, newer: &Object) -> Self {
+        ObjectDelta {
+            sprite: changed(&older.0, &newer.0),
+            position: changed(&older.1, &newer.1),
+            info: changed(&older.2, &newer.2),
+        }
+    }
+
+    fn apply(&self, obj: &mut Object, forward: bool) {
+        if let Some(sprite) = &self.sprite {
+            obj.0 = pick(sprite, forward).clone();
+        }
+        if let Some(position) = &self.position {
+            obj.1 = *pick(position, forward);
+        }
+        if let Some(info) = &self.info {
+            obj.2 = pick(info, forward).clone();
+        }
+    }
+
+    fn sprites(&self) -> impl Iterator<Item = &Sprite> {
+        self.sprite
+            .iter()
+            .flat_map(|(a, b)| std::iter::once(a).chain(std::iter::once(b)))
+    }
+
+    fn size(&self) -> usize {
+        let sprites = self.sprite.as_ref().map_or(0, |(a, b)| sprite_size(a) + sprite_size(b));
+
+        std::mem::size_of::<Self>() + sprites
+    }
+}
+
+// reversible change between two consecutive history entries. Objects
+// outside of the changed range stay as they are, inside it they are paired
+// by position with leftovers being removed or added objects.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Delta {
+    at: usize, // start of the changed object range
+    changed: Vec<ObjectDelta>,
+    removed: Vec<Object>,
+    added: Vec<Object>,
+    bookmarks: Option<(Bookmarks, Bookmarks)>,
+    groups: Option<(Groups, Groups)>,
+    selections: (Vec<usize>, Vec<usize>),
+}
+
+impl Delta {
+    fn between(older: &Entry, newer: &Entry) -> Self {
+        let (old, new) = (&older.scene.objects, &newer.scene.objects);
+
+        let prefix = old.iter().zip(new.iter()).take_while(|(a, b)| a == b).count();
+        let max_suffix = std::cmp::min(old.len(), new.len()) - prefix;
+        let suffix = old
+            .iter()
+            .rev()
+            .zip(new.iter().rev())
+            .take(max_suffix)
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        let old_range = &old[prefix..old.len() - suffix];
+        let new_range = &new[prefix..new.len() - suffix];
+        let paired = std::cmp::min(old_range.len(), new_range.len());
+
+        Delta {
+            at: prefix,
+            changed: old_range
+                .iter()
+                .zip(new_range.iter())
+                .map(|(a, b)| ObjectDelta::between(a, b))
+                .collect(),
+            removed: old_range[paired..].to_vec(),
+            added: new_range[paired..].to_vec(),
+            bookmarks: changed(&older.scene.bookmarks, &newer.scene.bookmarks),
+            groups: changed(&older.scene.groups, &newer.scene.groups),
+            selections: (older.selections.clone(), newer.selections.clone()),
+        }
+    }
+
+    fn apply(&self, entry: &mut Entry, forward: bool) {
+        let objects = &mut entry.scene.objects;
+        let start = self.at + self.changed.len();
+        let (gone, back) = if forward {
+            (&self.removed, &self.added)
+        } else {
+            (&self.added, &self.removed)
+        };
+
+        objects.splice(start..start + gone.len(), back.iter().cloned());
+        for (obj, delta) in objects[self.at..start].iter_mut().zip(self.changed.iter()) {
+            delta.apply(obj, forward);
+        }
+
+        if let Some(bookmarks) = &self.bookmarks {
+            entry.scene.bookmarks = pick(bookmarks, forward).clone();
+        }
+        if let Some(groups) = &self.groups {
+            entry.scene.groups = pick(groups, forward).clone();
+        }
+        entry.selections = pick(&self.selections, forward).clone();
+    }
+
+    fn sprites(&self) -> impl Iterator<Item = &Sprite> {
+        let objects = self.removed.iter().chain(self.added.iter()).map(|obj| &obj.0);
+
+        objects.chain(self.changed.iter().flat_map(ObjectDelta::sprites))
+    }
+
+    fn size(&self) -> usize {
+        let objects: usize = self.removed.iter().chain(self.added.iter()).map(object_size).sum();
+        let changes: usize = self.changed.iter().map(ObjectDelta::size).sum();
+
+        std::mem::size_of::<Self>() + objects + changes
+    }
+}
+
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+struct Entry {
+    scene: SceneV3,
+    selections: Vec<usize>,
+}
+
+impl From<Snapshot> for Entry {
+    fn from(snapshot: Snapshot) -> Self {
+        Entry {
+            scene: snapshot.scene.current(),
+            selections: snapshot.selections,
+        }
+    }
+}
+
+impl From<&Entry> for Snapshot {
+    fn from(entry: &Entry) -> Self {
+        Snapshot {
+            scene: Scene::V3(entry.scene.clone()),
+            selections: entry.selections.clone(),
+        }
+    }
+}
+
+// single state in the undo tree, reached from its parent via delta
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Node {
+    parent: Option<usize>,
+    delta: Option<Delta>, // from parent, none for root
+    children: Vec<usize>, // oldest first
+    redo: Option<usize>,  // child followed by redo, last visited
+    label: String,
+    time: SystemTime,
+}
+
+impl Node {
+    fn new(parent: Option<usize>, delta: Option<Delta>, label: String) -> Self {
+        Node {
+            parent,
+            delta,
+            children: Vec::new(),
+            redo: None,
+            label,
+            time: SystemTime::now(),
+        }
+    }
+}
+
+// state as listed in the undo tree view
+#[derive(Debug, Clone)]
+pub struct HistoryItem {
+    pub id: usize,
+    pub depth: usize, // branch nesting
+    pub label: String,
+    pub time: SystemTime,
+    pub current: bool,
+}
+
+// undo tree, only the current entry is kept whole, other states are reached
+// via deltas along the tree. Changes after undo start a new branch instead of
+// dropping the undone ones. Oldest leaves are dropped when over budget.
+// Can be saved next to the scene to undo across sessions.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct History {
+    current: Entry,
+    nodes: BTreeMap<usize, Node>, // by id, ids are chronological
+    at: usize,                    // id of current state
+    next: usize,
+    size: usize, // estimated bytes used by deltas
+    #[serde(skip)]
+    budget: usize, // set from config, not saved
+}
+
+impl Default for History {
+    fn default() -> Self {
+        let mut result = History {
+            current: Entry::default(),
+            nodes: BTreeMap::new(),
+            at: 0,
+            next: 0,
+            size: 0,
+            budget: DEFAULT_HISTORY_BUDGET,
+        };
+
+        result.clear(Snapshot::default());
+        result
+    }
+}
+
+impl History {
+    pub fn index(&self) -> usize {
+        self.at
+    }
+
+    // nothing happened since start or last clear
+    pub fn is_initial(&self) -> bool {
+        self.next == 1
+    }
+
+    pub fn budget(&self) -> usize {
+        self.budget
+    }
+
+    pub fn set_budget(&mut self, budget: usize) {
+        self.budget = budget;
+        self.trim();
+    }
+
+    // copy within given budget, for saving
+    pub fn trimmed(&self, budget: usize) -> History {
+        let mut result = self.clone();
+        result.set_budget(budget);
+
+        result
+    }
+
+    // current states hold the same scene, selections aside
+    pub fn same_scene(&self, other: &History) -> bool {
+        let (a, b) = (&self.current.scene, &other.current.scene);
+
+        a.objects == b.objects && a.bookmarks == b.bookmarks && a.groups == b.groups
+    }
+
+    pub fn clear(&mut self, snapshot: Snapshot) {
+        self.current = Entry::from(snapshot);
+        self.nodes.clear();
+        self.nodes.insert(0, Node::new(None, None, String::from("Start")));
+        self.at = 0;
+        self.next = 1;
+        self.size = 0;
+    }
+
+    // replaces current entry without adding a step
+    pub fn refresh(&mut self, snapshot: Snapshot) {
+        self.current = Entry::from(snapshot);
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot, label: String) {
+        let newer = Entry::from(snapshot);
+        let delta = Delta::between(&self.current, &newer);
+        let id = self.next;
+
+        if let Some(parent) = self.nodes.get_mut(&self.at) {
+            parent.children.push(id);
+            parent.redo = Some(id);
+        }
+
+        self.size += delta.size();
+        self.nodes.insert(id, Node::new(Some(self.at), Some(delta), label));
+        self.next += 1;
+        self.at = id;
+        self.current = newer;
+        self.trim();
+    }
+
+    pub fn undo(&mut self) -> Option<Snapshot> {
+        if self.step_up() {
+            Some(Snapshot::from(&self.current))
+        } else {
+            None
+        }
+    }
+
+    pub fn redo(&mut self) -> Option<Snapshot> {
+        let child = self.nodes.get(&self.at)?.redo?;
+        self.step_down(child);
+
+        Some(Snapshot::from(&self.current))
+    }
+
+    // goes to any state in the tree via the closest common parent
+    pub fn jump(&mut self, id: usize) -> Option<Snapshot> {
+        if !self.nodes.contains_key(&id) {
+            return None;
+        }
+
+        let target = self.path(id);
+        let common = target
+            .iter()
+            .zip(self.path(self.at).iter())
+            .take_while(|(a, b)| a == b)
+            .count();
+
+        while self.at != target[common - 1] && self.step_up() {}
+        for child in target[common..].iter() {
+            self.step_down(*child);
+        }
+
+        Some(Snapshot::from(&self.current))
+    }
+
+    // color indexes of texels in any state along the tree
+    pub fn used_colors(&self) -> BTreeSet<u8> {
+        let deltas = self.nodes.values().filter_map(|node| node.delta.as_ref());
+        let sprites = self
+            .current
+            .scene
+            .objects
+            .iter()
+            .map(|obj| &obj.0)
+            .chain(deltas.flat_map(Delta::sprites));
+
+        sprites
+            .flat_map(|sprite| sprite.frames.iter().flatten())
+            .flat_map(|t| std::iter::once(t.bg).chain(std::iter::once(t.fg)))
+            .collect()
+    }
+
+    // all states depth first, the oldest branch of each state continues
+    // at the same depth and newer ones are nested
+    pub fn list(&self) -> Vec<HistoryItem> {
+        let mut result = Vec::with_capacity(self.nodes.len());
+        let mut stack: Vec<(usize, usize)> = self.root().map(|id| (id, 0)).into_iter().collect();
+
+        while let Some((id, depth)) = stack.pop() {
+            let node = &self.nodes[&id];
+
+            result.push(HistoryItem {
+                id,
+                depth,
+                label: node.label.clone(),
+                time: node.time,
+                current: id == self.at,
+            });
+
+            for (i, child) in node.children.iter().enumerate().rev() {
+                stack.push((*child, depth + usize::from(i > 0)));
+            }
+        }
+
+        result
+    }
+
+    fn root(&self) -> Option<usize> {
+        self.nodes
+            .iter()
+            .find(|(_, node)| node.parent.is_none())
+            .map(|(id, _)| *id)
+    }
+
+    // ids from root to given state
+    fn path(&self, id: usize) -> Vec<usize> {
+        let mut result = vec![id];
+
+        while let Some(parent) = self.nodes.get(result.last().unwrap()).and_then(|node| node.parent) {
+            result.push(parent);
+        }
+
+        result.reverse();
+        result
+    }
+
+    fn step_up(&mut self) -> bool {
+        let node = match self.nodes.get(&self.at) {
+            Some(node) => node,
+            None => return false,
+        };
+        let (parent, delta) = match (node.parent, &node.delta) {
+            (Some(parent), Some(delta)) => (parent, delta),
+            _ => return false,
+        };
+
+        delta.apply(&mut self.current, false);
+        if let Some(parent_node) = self.nodes.get_mut(&parent) {
+            parent_node.redo = Some(self.at);
+        }
+        self.at = parent;
+
+        true
+    }
+
+    fn step_down(&mut self, child: usize) {
+        if let Some(delta) = self.nodes.get(&child).and_then(|node| node.delta.as_ref()) {
+            delta.apply(&mut self.current, true);
+        }
+
+        if let Some(node) = self.nodes.get_mut(&self.at) {
+            node.redo = Some(child);
+        }
+        self.at = child;
+    }
+
+    // drop oldest leaves or root over budget, current state and its parent stay
+    fn trim(&mut self) {
+        while self.size > self.budget && self.nodes.len() > 2 {
+            let oldest = self
+                .nodes
+                .iter()
+                .find(|(id, node)| {
+                    **id != self.at && (node.children.is_empty() || (node.parent.is_none() && node.children.len() == 1))
+                })
+                .map(|(id, _)| *id);
+
+            match oldest {
+                Some(id) => self.drop_node(id),
+                None => break,
+            }
+        }
+    }
+
+    fn drop_node(&mut self, id: usize) {
+        let node = match self.nodes.remove(&id) {
+            Some(node) => node,
+            None => return,
+        };
+
+        if let Some(delta) = &node.delta {
+            self.size -= delta.size();
+        }
+
+        match node.parent {
+            Some(parent) => {
+                if let Some(parent_node) = self.nodes.get_mut(&parent) {
+                    parent_node.children.retain(|c| *c != id);
+                    if parent_node.redo == Some(id) {
+                        parent_node.redo = parent_node.children.last().copied();
+                    }
+                }
+            }
+            None => {
+                // only child becomes the new root
+                for child in node.children.iter() {
+                    if let Some(child_node) = self.nodes.get_mut(child) {
+                        child_node.parent = None;
+                        if let Some(delta) = child_node.delta.take() {
+                            self.size -= delta.size();
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn changed<T: Clone + PartialEq>(older: &T, newer: &T) -> Option<(T, T)> {
+    if older == newer {
+        None
+    } else {
+        Some((older.clone(), newer.clone()))
+    }
+}
+
+fn pick<T>(pair: &(T, T), forward: bool) -> &T {
+    if forward {
+        &pair.1
+    } else {
+        &pair.0
+    }
+}
+
+fn sprite_size(sprite: &Sprite) -> usize {
+    let texels: usize = sprite.frames.iter().map(|frame| frame.len()).sum();
+
+    std::mem::size_of::<Sprite>() + texels * std::mem::size_of::<Texel>()
+}
+
+fn object_size(obj: &Object) -> usize {
+    sprite_size(&obj.0) + std::mem::size_of::<Object>()
+}
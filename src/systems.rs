@@ -2,11 +2,13 @@ use crate::resources::{FrameBuffer, State};
 use legion::*;
 
 mod action_handler;
+mod autosave_handler;
 mod history_handler;
 mod input_handler;
 mod renderers;
 
 pub use action_handler::handle_actions;
+pub use autosave_handler::autosave;
 pub use history_handler::preserve_history;
 pub use input_handler::handle_input;
 pub use renderers::*;
@@ -18,11 +20,16 @@ impl TexelSystems {
         out.flip_buffers();
 
         handle_input(state, resources);
-        handle_actions(world, state);
+        handle_actions(world, state, resources);
         preserve_history(world, state);
+        autosave(world, state);
 
+        render_grid(state, out);
+        render_guides(state, out);
         render_sprites(world, state, out);
+        render_history(state, out);
         render_subselections(world, state, out);
+        render_rulers(state, out);
         render_meta_info(world, state, out);
         render_cmdline(state, resources, out);
     }
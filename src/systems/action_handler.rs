@@ -1,23 +1,31 @@
 use crate::common::{
-    fio, Action, Clipboard, ClipboardOp, Error, Layout, MetadataType, Mode, OnQuit, Scene, SceneExt, SelectMode,
+    add_max, apply_mask_op, fio, mirror_symbol, world_groups, Action, Alignment, Anchor, Animation, Clipboard,
+    ClipboardOp, ColorDepth, ColorTable, Distribution, Error, ExtStyle, ExtStyleMap, FrameOp, Grid, GuideChange,
+    Guides, Keyframes, Layout, MaskOp, MetadataType, Mode, ObjectInfo, OnQuit, PaletteFile, PaletteOp, Playback,
+    RemapOp, RemapSource, Remaps, Rgb, RgbTarget, Scene, SceneExt, SelectMode, SpriteExt, TimelineChange,
 };
 use crate::components::*;
 use crate::os::Terminal;
-use crate::resources::{State, PALETTE_H, PALETTE_OFFSET, PALETTE_W};
+use crate::resources::{ColorPalette, State, SymbolPalette, PALETTE_H, PALETTE_W};
 use fio::ExportFormat;
 use legion::systems::CommandBuffer;
 use legion::*;
+use std::collections::{BTreeMap, BTreeSet};
 use texel_types::{ColorMode, SymbolStyle, Texels, Which};
 
 const NEW_POSITION: Position = Position { x: 10, y: 10, z: 0 };
 
-pub fn handle_actions(world: &mut World, state: &mut State) {
+pub fn handle_actions(world: &mut World, state: &mut State, resources: &mut Resources) {
     while let Some(action) = state.pop_action() {
-        state.dirty |= match action {
+        let name = action.name();
+        let dirtied = match action {
             Action::None => false,
             Action::New(force) => new(force, world, state),
             Action::Undo => undo(world, state),
             Action::Redo => redo(world, state),
+            Action::UndoList(id) => undo_list(id, state),
+            Action::UndoTo(id) => undo_to(id, world, state),
+            Action::Recover(discard) => recover(discard, world, state),
             Action::Clipboard(op) => clipboard(op, world, state),
             Action::ToggleMetadata => toggle_metadata(state),
             Action::SetMetadata(mt) => set_metadata(mt, world, state),
@@ -25,6 +33,16 @@ pub fn handle_actions(world: &mut World, state: &mut State) {
             Action::Duplicate(count) => duplicate_selected(count, world, state),
             Action::NewFrame => new_frame_on_selected(world, state),
             Action::DeleteFrame => delete_frame_on_selected(world, state),
+            Action::Frame(op) => frame_op_on_selected(op, world, state),
+            Action::Timeline(change) => timeline(change, world, state),
+            Action::SetRgb(rgb, target) => set_rgb(rgb, target, world, state, resources),
+            Action::Palette(op) => palette(op, state, resources),
+            Action::Quantize(depth) => quantize_selected(depth, world, state),
+            Action::Remap(op) => remap(op, world, state, resources),
+            Action::Preview(depth) => {
+                state.preview = depth;
+                false
+            }
             Action::Bookmark(index, true) => set_bookmark(index, state.offset(), world),
             Action::Bookmark(index, false) => jump_to_bookmark(index, world, state),
             Action::Cancel => cancel(world, state),
@@ -34,6 +52,7 @@ pub fn handle_actions(world: &mut World, state: &mut State) {
             Action::ApplyColor(cm) => apply_color_to_selected(cm, world, state),
             Action::ApplySymbol(sym) => apply_symbol_to_selected(sym, world, state),
             Action::ApplyStyle(style) => apply_style_to_selected(style, world, state),
+            Action::ApplyExtStyle(style) => apply_ext_style_to_selected(style, world, state),
             Action::ApplyRegion => apply_region(subselection(world, state), world, state),
             Action::PickColor(cm) => pick_color(cm, world, state),
             Action::Deselect => clear_subselection(world) || deselect_obj(world),
@@ -47,14 +66,91 @@ pub fn handle_actions(world: &mut World, state: &mut State) {
             Action::WriteAndQuit(path) => write_and_quit(path, world, state),
             Action::Read(path) => read_scene_from_file(path, world, state),
             Action::Tutorial => tutorial(world, state),
-            Action::Export(format, path) => export_to_file(format, &path, world, state),
+            Action::Export(format, path, include_hidden, depth) => {
+                export_to_file(format, &path, include_hidden, depth, world, state)
+            }
             Action::ShowHelp(index) => show_help(index, state),
             Action::ClearBlank => clear_blank_texels(world, state),
+            Action::Symmetry(symmetry) => state.set_symmetry(symmetry),
+            Action::ToggleSymmetry => state.toggle_symmetry(),
+            Action::OnionSkin(onion_skin) => state.set_onion_skin(onion_skin),
+            Action::ToggleOnionSkin => state.toggle_onion_skin(),
+            Action::Outline(symbol) => outline_selected(symbol, world, state),
+            Action::Shadow(offset, as_sprite) => shadow_selected(offset, as_sprite, world, state),
+            Action::MagicWand(op) => magic_wand(op, world, state),
+            Action::Trim => trim_selected(world, state),
+            Action::Canvas(dim, anchor) => canvas_on_selected(dim, anchor, world, state),
+            Action::Group => group_selected(world, state),
+            Action::Ungroup => ungroup_selected(world, state),
+            Action::EnterGroup => enter_group(world, state),
+            Action::LeaveGroup => leave_group(world, state),
+            Action::Hide => hide_selected(world, state),
+            Action::Show => show_hidden(world, state),
+            Action::Lock => lock_selected(world, state),
+            Action::Unlock => unlock_locked(world, state),
+            Action::Guide(change) => {
+                let before = state.guides.clone();
+                match change {
+                    GuideChange::Add(vertical, at) => {
+                        let cursor = state.cursor + state.offset();
+                        let at = at.unwrap_or(if vertical { cursor.x } else { cursor.y });
+                        state.guides.add(vertical, at)
+                    }
+                    GuideChange::Clear => state.guides.clear(),
+                }
+                if state.guides != before {
+                    state.settings_changed();
+                }
+                false
+            }
+            Action::ToggleRulers => {
+                state.rulers = !state.rulers;
+                false
+            }
+            Action::Play(playback) => play(playback, world, state),
+            Action::Tick => tick(world, state),
+            Action::ToggleCrosshair => {
+                state.crosshair = !state.crosshair;
+                false
+            }
+            Action::Grid(change) => {
+                let color = state.color(ColorMode::Bg);
+                let before = state.grid;
+                state.grid.apply(change, color);
+                if state.grid != before {
+                    state.settings_changed();
+                }
+                false
+            }
+            Action::SetWand(wand) => {
+                state.wand = wand;
+                false
+            }
             Action::ReverseMode => {
                 reverse_mode(world, state); // NOTE: reverse returns if reverted, not dirty state
                 false
             }
         };
+
+        if dirtied {
+            prune_ext_styles(world);
+            state.set_dirty(name);
+        }
+    }
+}
+
+// styles of removed texels shouldn't come back with new ones drawn in their place
+fn prune_ext_styles(world: &mut World) {
+    let mut query = <(Read<Sprite>, Write<ExtStyleMap>)>::query();
+    for (sprite, ext_styles) in query.iter_mut(world) {
+        ext_styles.retain_texels(sprite);
+    }
+}
+
+// texels shifted to keep sprite origin at 0,0, extended styles go with them
+fn ext_styles_moved(ext_styles: &mut Option<&mut ExtStyleMap>, bounds: &Bounds) {
+    if let Some(ext_styles) = ext_styles {
+        ext_styles.origin_moved(*bounds.position());
     }
 }
 
@@ -114,6 +210,11 @@ fn clear_subselection(world: &mut World) -> bool {
         todo.remove(*entity);
     };
 
+    let mut query = <(Entity, Read<SelectionMask>)>::query();
+    for (entity, _) in query.iter(world) {
+        todo.remove(*entity);
+    }
+
     todo.flush(world, &mut Resources::default());
 
     false
@@ -128,21 +229,12 @@ fn set_mode(mode: Mode, world: &mut World, state: &mut State) -> bool {
     }
 
     if match mode {
-        Mode::Quitting(OnQuit::Check) => {
-            if state.unsaved_changes() {
-                state.set_error(Error::execution("Unsaved changes, use q! to quit without saving"));
-                false
-            } else {
-                true
-            }
+        Mode::Quitting(OnQuit::Check) if state.unsaved_changes() => {
+            state.set_error(Error::execution("Unsaved changes, use q! to quit without saving"))
         }
-        Mode::Quitting(OnQuit::Save) => {
-            if state.unsaved_changes() {
-                if let Err(err) = save_scene(&None, world, state) {
-                    state.set_error(err)
-                } else {
-                    true
-                }
+        Mode::Quitting(OnQuit::Save) if state.unsaved_changes() => {
+            if let Err(err) = save_scene(&None, world, state) {
+                state.set_error(err)
             } else {
                 true
             }
@@ -163,13 +255,7 @@ fn set_mode(mode: Mode, world: &mut World, state: &mut State) -> bool {
             _ => state.set_error(Error::execution("Multiple objects selected")),
         },
         Mode::SelectColor(_, _) => {
-            let ts = Terminal::terminal_size();
-
-            state.cursor = Position2D {
-                x: PALETTE_OFFSET,
-                y: i32::from(ts.1) - 14,
-            };
-
+            state.cursor = ColorPalette::picker_pos();
             true
         }
         Mode::Object(SelectMode::Region) => {
@@ -272,7 +358,12 @@ fn mark_subselection(world: &mut World, state: &State) -> Option<Bounds> {
             None
         }
     } else {
-        // initiating new selection/edit
+        // initiating new selection/edit, replaces texel mask
+        let mut query = <(Entity, Read<SelectionMask>)>::query();
+        for (entity, _) in query.iter(world) {
+            todo.remove(*entity);
+        }
+
         let pos = state.cursor;
         todo.extend(vec![(pos, Dimension::unit(), Subselection::at(pos))]);
         None
@@ -291,7 +382,8 @@ fn apply_region(region: Option<Bounds>, world: &mut World, state: &mut State) ->
 
     deselect_obj(world);
 
-    let mut query = <(Entity, Read<Position>, Read<Dimension>)>::query().filter(component::<Selectable>());
+    let mut query = <(Entity, Read<Position>, Read<Dimension>)>::query()
+        .filter(component::<Selectable>() & !component::<Locked>() & !component::<Hidden>());
     let mut todo = CommandBuffer::new(world);
     for (entity, pos, dim) in query.iter(world) {
         // any point inside region -> select
@@ -302,6 +394,7 @@ fn apply_region(region: Option<Bounds>, world: &mut World, state: &mut State) ->
     }
 
     todo.flush(world, &mut Resources::default());
+    select_group_members(world, state);
 
     clear_subselection(world);
     reverse_mode(world, state);
@@ -314,8 +407,8 @@ fn select_obj_relative(forward: bool, sticky: bool, world: &mut World, state: &S
     let mut start = 0usize;
     let viewport = viewport_bounds(state);
 
-    let mut query =
-        <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query().filter(component::<Selectable>());
+    let mut query = <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query()
+        .filter(component::<Selectable>() & !component::<Locked>() & !component::<Hidden>());
     for (entity, pos, _, selected) in query.iter(world).filter(|item| {
         let p = *(item.1);
         let d = *(item.2);
@@ -371,8 +464,8 @@ fn select_obj_relative(forward: bool, sticky: bool, world: &mut World, state: &S
 fn select_obj_all(world: &mut World, state: &State) -> bool {
     let viewport = viewport_bounds(state);
     let mut todo = CommandBuffer::new(world);
-    let mut query =
-        <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query().filter(component::<Selectable>());
+    let mut query = <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query()
+        .filter(component::<Selectable>() & !component::<Locked>() & !component::<Hidden>());
     for (entity, _, _, _) in query.iter(world).filter(|item| {
         let p = *(item.1);
         let d = *(item.2);
@@ -396,8 +489,8 @@ fn select_obj_at(at: Position2D, sticky: bool, world: &mut World, state: &State)
         deselect_obj(world);
     }
 
-    let mut query =
-        <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query().filter(component::<Selectable>());
+    let mut query = <(Entity, Read<Position>, Read<Dimension>, TryRead<Selection>)>::query()
+        .filter(component::<Selectable>() & !component::<Locked>() & !component::<Hidden>());
     for (entity, _, _, _) in query.iter(world).filter(|item| {
         let p = *(item.1);
         let d = *(item.2);
@@ -418,13 +511,17 @@ fn select_obj_at(at: Position2D, sticky: bool, world: &mut World, state: &State)
 }
 
 fn select_obj(which: Which<Position2D>, sticky: bool, world: &mut World, state: &State) -> bool {
-    match which {
+    let result = match which {
         Which::Next => select_obj_relative(true, sticky, world, state),
         Which::Previous => select_obj_relative(false, sticky, world, state),
         Which::All => select_obj_all(world, state),
         Which::At(pos) => select_obj_at(pos, sticky, world, state),
         // Which::At(pos) => select_obj_at(pos, sticky, world),
-    }
+    };
+
+    select_group_members(world, state);
+
+    result
 }
 
 fn delete_object(world: &mut World, state: &mut State) -> bool {
@@ -497,11 +594,8 @@ fn translate_subselection(t: Translation, area_bounds: Option<Bounds>, world: &m
 fn translate_object(t: Translation, world: &mut World, state: &mut State) -> bool {
     let ts = Terminal::terminal_size();
     let screen_dim = Dimension::from_wh(ts.0, ts.1);
-    let palette_pos = Position2D {
-        x: PALETTE_OFFSET,
-        y: i32::from(ts.1) - 14,
-    };
-    let palette_dim = Dimension::from_wh(PALETTE_W as u16, PALETTE_H as u16);
+    let palette_pos = ColorPalette::picker_pos();
+    let palette_dim = Dimension::from_wh(PALETTE_W as u16, PALETTE_H as u16 - 1); // without cmdline row
     let palette_bounds = Bounds::Binding(palette_pos, palette_dim);
 
     match state.mode() {
@@ -521,12 +615,35 @@ fn translate_object(t: Translation, world: &mut World, state: &mut State) -> boo
                 let screen_bounds = Bounds::Free(Position2D::default(), screen_dim);
                 state.offset_mut().apply(t, screen_bounds);
             } else {
-                let mut query = <(Write<Position>, Read<Dimension>)>::query().filter(component::<Selection>());
+                // snapping moves by whole cells, see input handler for dragging
+                let snap = match t {
+                    Translation::Relative(x, y, z) if state.grid.snap && (x != 0 || y != 0) => Some(
+                        Translation::Relative(x * state.grid.cell_w(), y * state.grid.cell_h(), z),
+                    ),
+                    Translation::ToEdge(_) if state.grid.snap => Some(t),
+                    _ => None,
+                };
+
+                let mut query = <(Write<Position>, Read<Dimension>)>::query()
+                    .filter(component::<Selection>() & !component::<Locked>());
                 for (position, dim) in query.iter_mut(world) {
                     let screen_bounds = Bounds::Free(Position2D::default(), screen_dim - *dim);
 
-                    if position.apply(t, screen_bounds) {
+                    let before = *position;
+
+                    if let Some(snap_t) = snap {
+                        position.apply(snap_t, screen_bounds);
+                        let snapped = state.grid.snap((*position).into());
+                        position.x = snapped.x;
+                        position.y = snapped.y;
                         changed = true;
+                    } else if position.apply(t, screen_bounds) {
+                        changed = true;
+                    }
+
+                    if state.grid.snap {
+                        position.x = state.guides.stop_x(before.x, position.x, dim.w);
+                        position.y = state.guides.stop_y(before.y, position.y, dim.h);
                     }
                 }
             }
@@ -547,16 +664,29 @@ fn translate_object(t: Translation, world: &mut World, state: &mut State) -> boo
 fn apply_color_to_selected(cm: ColorMode, world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
     let color = state.color(cm);
-    let sel_bounds = subselection_bounds(world, state);
+    let areas = edit_areas(world, state);
 
-    let mut query = <(Write<Sprite>, Write<Position>)>::query().filter(component::<Selection>());
-    for (sprite, pos) in query.iter_mut(world) {
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
         if state.mode() == Mode::Edit {
             let pos2d: Position2D = (*pos).into();
-            let rel_bounds = sel_bounds - pos2d;
+            let mirrored = mirrored_positions(&areas, pos2d, *dim, state);
 
-            if (*sprite).apply_color(cm, color, rel_bounds) {
-                changed = true;
+            for area in areas.iter() {
+                if (*sprite).apply_color(cm, color, *area - pos2d) {
+                    changed = true;
+                }
+            }
+
+            for (mirror_pos, _, _) in mirrored {
+                let pos2d: Position2D = (*pos).into();
+                let bounds = sprite.apply_color_at(cm, color, mirror_pos - pos2d);
+
+                ext_styles_moved(&mut ext_styles, &bounds);
+
+                *pos += *bounds.position();
+                *dim = *bounds.dimension();
             }
         } else if sprite.fill_color(cm, color) {
             changed = true;
@@ -572,23 +702,27 @@ fn apply_color_to_selected(cm: ColorMode, world: &mut World, state: &mut State)
 
 fn clear_symbol_on_selected(world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
-    let sel_bounds = subselection_bounds(world, state);
+    let areas = edit_areas(world, state);
 
-    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>)>::query().filter(component::<Selection>());
-    for (sprite, pos, dim) in query.iter_mut(world) {
-        let pos2d: Position2D = (*pos).into();
-        let rel_bounds = sel_bounds - pos2d;
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        for area in areas.iter() {
+            let pos2d: Position2D = (*pos).into();
+            let rel_bounds = *area - pos2d;
 
-        match (*sprite).clear_symbol(rel_bounds) {
-            None => {
-                changed = true;
-            } // no change, symbol was applied in bounds
-            Some(bounds) => {
-                // changed pos or dim => apply new bounds
-                *pos += *bounds.position();
-                *dim = *bounds.dimension();
+            match (*sprite).clear_symbol(rel_bounds) {
+                None => {
+                    changed = true;
+                } // no change, symbol was applied in bounds
+                Some(bounds) => {
+                    // changed pos or dim => apply new bounds
+                    ext_styles_moved(&mut ext_styles, &bounds);
+                    *pos += *bounds.position();
+                    *dim = *bounds.dimension();
 
-                changed = true;
+                    changed = true;
+                }
             }
         }
     }
@@ -600,6 +734,70 @@ fn clear_symbol_on_selected(world: &mut World, state: &mut State) -> bool {
     changed
 }
 
+fn selection_mask(world: &mut World) -> Option<SelectionMask> {
+    <Read<SelectionMask>>::query().iter(world).next().cloned()
+}
+
+// areas to edit, texel mask if present, subselection or cursor otherwise
+fn edit_areas(world: &mut World, state: &State) -> Vec<Bounds> {
+    match selection_mask(world) {
+        Some(mask) => mask.0.into_iter().map(Bounds::point).collect(),
+        None => vec![subselection_bounds(world, state)],
+    }
+}
+
+fn copy_mask(sprite: &Sprite, mask: &SelectionMask, pos: Position2D) -> Texels {
+    let top_left = match mask.bounds() {
+        Some(bounds) => *bounds.position() - pos,
+        None => return Texels::new(),
+    };
+
+    sprite
+        .frame_iter()
+        .filter(|t| mask.0.contains(&(t.pos + pos)))
+        .map(|t| t.moved_from(top_left))
+        .collect()
+}
+
+fn magic_wand(op: MaskOp, world: &mut World, state: &mut State) -> bool {
+    let mut query = <(Read<Sprite>, Read<Position>)>::query().filter(component::<Selection>());
+    let found = if let Some((sprite, pos)) = query.iter(world).next() {
+        let pos2d: Position2D = (*pos).into();
+        let at = state.cursor + state.offset();
+
+        let positions: Vec<Position2D> = state
+            .wand
+            .select(sprite, at - pos2d)
+            .into_iter()
+            .map(|p| p + pos2d)
+            .collect();
+        Some(positions)
+    } else {
+        None
+    };
+
+    let positions = match found {
+        Some(positions) => positions,
+        None => return state.set_error(Error::execution("No object selected")),
+    };
+
+    if positions.is_empty() && op != MaskOp::Subtract {
+        return state.set_error(Error::execution("No texel under cursor"));
+    }
+
+    let mut mask = selection_mask(world).unwrap_or_default();
+    apply_mask_op(&mut mask.0, positions, op);
+
+    // mask replaces any rectangular subselection
+    clear_subselection(world);
+
+    if !mask.0.is_empty() {
+        world.extend(vec![(mask,)]);
+    }
+
+    false
+}
+
 fn subselection(world: &mut World, state: &State) -> Option<Bounds> {
     let mut query = <(Read<Position2D>, Read<Dimension>)>::query().filter(component::<Subselection>());
     if let Some((pos, dim)) = query.iter(world).next() {
@@ -617,8 +815,15 @@ fn new_frame_on_selected(world: &mut World, state: &mut State) -> bool {
         return false;
     }
 
-    let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
-    for sprite in query.iter_mut(world) {
+    let mut query =
+        <(Write<Sprite>, TryWrite<Animation>, TryWrite<ExtStyleMap>)>::query().filter(component::<Selection>());
+    for (sprite, animation, ext_styles) in query.iter_mut(world) {
+        if let Some(animation) = animation {
+            animation.frame_inserted(sprite.index);
+        }
+        if let Some(ext_styles) = ext_styles {
+            ext_styles.frame_inserted(sprite.index);
+        }
         sprite.new_frame();
         changed = true;
     }
@@ -634,9 +839,18 @@ fn delete_frame_on_selected(world: &mut World, state: &mut State) -> bool {
         return false;
     }
 
-    let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
-    for sprite in query.iter_mut(world) {
+    let mut query =
+        <(Write<Sprite>, TryWrite<Animation>, TryWrite<ExtStyleMap>)>::query().filter(component::<Selection>());
+    for (sprite, animation, ext_styles) in query.iter_mut(world) {
+        let index = sprite.index;
+
         if sprite.delete_frame() {
+            if let Some(animation) = animation {
+                animation.frame_removed(index);
+            }
+            if let Some(ext_styles) = ext_styles {
+                ext_styles.frame_removed(index);
+            }
             changed = true;
         }
     }
@@ -644,6 +858,114 @@ fn delete_frame_on_selected(world: &mut World, state: &mut State) -> bool {
     changed
 }
 
+fn frame_op_on_selected(op: FrameOp, world: &mut World, state: &mut State) -> bool {
+    let selected = <Read<Selection>>::query().iter(world).count();
+    if selected == 0 {
+        return state.set_error(Error::execution("No objects selected"));
+    }
+
+    match op {
+        FrameOp::Duplicate => new_frame_on_selected(world, state),
+        FrameOp::Copy => {
+            if selected > 1 {
+                return state.set_error(Error::execution("Can only copy frame from single object"));
+            }
+
+            let mut query = <Read<Sprite>>::query().filter(component::<Selection>());
+            if let Some(sprite) = query.iter(world).next() {
+                state.clipboard = Clipboard::Frame(sprite.frames[sprite.index].clone());
+            }
+
+            false
+        }
+        FrameOp::Paste => {
+            let texels: Texels = match &state.clipboard {
+                Clipboard::Empty => return state.set_error(Error::execution("Clipboard is empty")),
+                clipboard => clipboard.clone().into(),
+            };
+
+            insert_frame_on_selected(Some(texels), None, world)
+        }
+        FrameOp::Insert(index) => insert_frame_on_selected(None, Some(index), world),
+        FrameOp::Move(right) => {
+            let mut changed = false;
+            let mut query =
+                <(Write<Sprite>, TryWrite<Animation>, TryWrite<ExtStyleMap>)>::query().filter(component::<Selection>());
+            for (sprite, animation, ext_styles) in query.iter_mut(world) {
+                let index = sprite.index;
+                let target = if right { index + 1 } else { std::cmp::max(index, 1) - 1 };
+
+                if sprite.swap_frames(index, target) {
+                    if let Some(animation) = animation {
+                        animation.frames_swapped(index, target);
+                    }
+                    if let Some(ext_styles) = ext_styles {
+                        ext_styles.frames_swapped(index, target);
+                    }
+                    changed = true;
+                }
+            }
+
+            changed
+        }
+        FrameOp::Swap(a, b) => {
+            let mut changed = false;
+            let mut query =
+                <(Write<Sprite>, TryWrite<Animation>, TryWrite<ExtStyleMap>)>::query().filter(component::<Selection>());
+            for (sprite, animation, ext_styles) in query.iter_mut(world) {
+                if sprite.swap_frames(a, b) {
+                    if let Some(animation) = animation {
+                        animation.frames_swapped(a, b);
+                    }
+                    if let Some(ext_styles) = ext_styles {
+                        ext_styles.frames_swapped(a, b);
+                    }
+                    changed = true;
+                }
+            }
+
+            if !changed {
+                return state.set_error(Error::execution("Frame index out of bounds"));
+            }
+
+            changed
+        }
+    }
+}
+
+// inserts given or blank frame at index, after current frame by default
+fn insert_frame_on_selected(texels: Option<Texels>, index: Option<usize>, world: &mut World) -> bool {
+    let mut changed = false;
+
+    let mut query = <(
+        Write<Sprite>,
+        Write<Position>,
+        Write<Dimension>,
+        TryWrite<Animation>,
+        TryWrite<ExtStyleMap>,
+    )>::query()
+    .filter(component::<Selection>());
+    for (sprite, pos, dim, animation, mut ext_styles) in query.iter_mut(world) {
+        let index = std::cmp::min(index.unwrap_or(sprite.index + 1), sprite.frame_count());
+        let bounds = sprite.insert_frame(index, texels.clone().unwrap_or_default());
+
+        if let Some(animation) = animation {
+            animation.blank_frame_inserted(index);
+        }
+        if let Some(ext_styles) = ext_styles.as_mut() {
+            ext_styles.blank_frame_inserted(index);
+        }
+
+        // texels shifted to 0,0 => move position so nothing moves visually
+        ext_styles_moved(&mut ext_styles, &bounds);
+        *pos += *bounds.position();
+        *dim = *bounds.dimension();
+        changed = true;
+    }
+
+    changed
+}
+
 fn set_bookmark(index: usize, location: Position2D, world: &mut World) -> bool {
     let mut query = <(Write<Position2D>, Read<Bookmark>)>::query();
     if let Some((pos, _)) = query.iter_mut(world).find(|(_, bm)| bm.0 == index) {
@@ -677,12 +999,28 @@ fn show_help(index: usize, state: &mut State) -> bool {
 }
 
 fn clear_blank_texels(world: &mut World, state: &mut State) -> bool {
-    use crate::common::SpriteExt;
+    // only clear in texel selection if there's one
+    let areas = match (subselection(world, state), selection_mask(world)) {
+        (Some(bounds), _) => Some(vec![bounds]),
+        (None, Some(mask)) => Some(mask.0.into_iter().map(Bounds::point).collect::<Vec<Bounds>>()),
+        (None, None) => None,
+    };
 
-    let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
+    let mut query = <(Write<Sprite>, Read<Position>)>::query().filter(component::<Selection>());
     let mut changed = false;
-    for sprite in query.iter_mut(world) {
-        sprite.clear_blank_texels(None);
+    for (sprite, pos) in query.iter_mut(world) {
+        let pos2d: Position2D = (*pos).into();
+
+        match &areas {
+            Some(areas) => {
+                for area in areas.iter() {
+                    sprite.clear_blank_texels(Some(*area - pos2d));
+                }
+            }
+            None => {
+                sprite.clear_blank_texels(None);
+            }
+        }
         changed = true;
     }
 
@@ -693,114 +1031,820 @@ fn clear_blank_texels(world: &mut World, state: &mut State) -> bool {
     }
 }
 
-fn apply_layout_to_selected(layout: Layout, world: &mut World, state: &mut State) -> bool {
-    use rand::Rng;
-    let mut rng = rand::rng();
-
-    let bounds = viewport_bounds(state);
-
-    match layout {
-        Layout::None => false,
-        Layout::Column(cols, padding) => {
-            let mut col_sizes = [0i32].repeat(cols);
-            let mut row_sizes = Vec::new();
-            let mut start_x = i32::MAX;
-            let mut start_y = i32::MAX;
-            let mut positions = Vec::new();
-            let mut moved = 0;
-
-            let mut query = <(Write<Position>, Read<Dimension>)>::query().filter(component::<Selection>());
-            for (i, (pos, dim)) in query.iter_mut(world).enumerate() {
-                let col = i % cols;
-                let row = i / cols;
-
-                if row_sizes.len() <= row {
-                    row_sizes.push(0i32);
-                }
-
-                col_sizes[col] = std::cmp::max(col_sizes[col], i32::from(dim.w));
-                row_sizes[row] = std::cmp::max(row_sizes[row], i32::from(dim.h));
+fn outline_selected(symbol: char, world: &mut World, state: &mut State) -> bool {
+    let color = state.color(ColorMode::Fg);
+    let mut changed = false;
 
-                if pos.x < start_x {
-                    start_x = pos.x;
-                }
-                if pos.y < start_y {
-                    start_y = pos.y;
-                }
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        let bounds = sprite.outline(symbol, color);
 
-                positions.push(pos); // we can't re-iterate so keep the references
-            }
+        // changed pos or dim => apply new bounds
+        ext_styles_moved(&mut ext_styles, &bounds);
+        *pos += *bounds.position();
+        *dim = *bounds.dimension();
+        changed = true;
+    }
 
-            for (i, pos) in positions.into_iter().enumerate() {
-                let col = i % cols;
-                let row = i / cols;
+    if !changed {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
+    }
+}
 
-                let offset_x = (if col > 0 { col_sizes[col - 1] } else { 0 } + i32::from(padding.0)) * col as i32;
-                let offset_y = (if row > 0 { row_sizes[row - 1] } else { 0 } + i32::from(padding.1)) * row as i32;
+fn shadow_selected(offset: Position2D, as_sprite: bool, world: &mut World, state: &mut State) -> bool {
+    let mut changed = false;
+    let mut shadows = Vec::new();
+
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        if as_sprite {
+            // shadow goes one z below the source
+            let shadow_pos = Position {
+                x: pos.x + offset.x,
+                y: pos.y + offset.y,
+                z: pos.z + 1,
+            };
+            shadows.push((sprite.shadow_sprite(), shadow_pos));
+        } else {
+            let bounds = sprite.drop_shadow(offset);
 
-                pos.x = start_x + offset_x;
-                pos.y = start_y + offset_y;
-                moved += 1;
-            }
+            ext_styles_moved(&mut ext_styles, &bounds);
 
-            moved > 0
+            *pos += *bounds.position();
+            *dim = *bounds.dimension();
         }
-        Layout::Random => {
-            let mut moved = 0;
-            let mut query = <(Write<Position>, Read<Dimension>)>::query().filter(component::<Selection>());
-            for (pos, dim) in query.iter_mut(world) {
-                let bounds_x = bounds.position().x;
-                let bounds_y = bounds.position().y;
-                let bounds_w = i32::from(bounds.dimension().w);
-                let bounds_h = i32::from(bounds.dimension().h);
-                let dim_w = i32::from(dim.w);
-                let dim_h = i32::from(dim.h);
-
-                if dim_w < bounds_w && dim_h < bounds_h {
-                    let x: i32 = rng.random_range(bounds_x..bounds_x + bounds_w - dim_w);
-                    let y: i32 = rng.random_range(bounds_y..bounds_y + bounds_h - dim_h);
-                    pos.x = x;
-                    pos.y = y;
-                    moved += 1
-                }
-            }
+        changed = true;
+    }
 
-            moved > 0
+    for (sprite, pos) in shadows.into_iter() {
+        if let Err(err) = import_sprite(
+            sprite,
+            Some(pos),
+            false,
+            ObjectInfo::in_group(state.entered_group),
+            world,
+            state,
+        ) {
+            return state.set_error(err);
         }
     }
-}
 
-fn change_frame_on_selected(which: Which<usize>, world: &mut World, state: &mut State) -> bool {
-    if <Read<Selection>>::query().iter(world).count() == 0 {
-        return state.set_error(Error::execution("No objects selected"));
+    if !changed {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
     }
+}
 
+fn trim_selected(world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
-    let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
-    for sprite in query.iter_mut(world) {
-        sprite.apply_frame_change(which);
+
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        let bounds = sprite.trim();
+
+        // texels shifted to 0,0 => move position so nothing moves visually
+        ext_styles_moved(&mut ext_styles, &bounds);
+        *pos += *bounds.position();
+        *dim = *bounds.dimension();
         changed = true;
     }
 
-    changed
+    if !changed {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
+    }
 }
 
-fn apply_style_to_selected(style: SymbolStyle, world: &mut World, state: &mut State) -> bool {
+fn canvas_on_selected(canvas: Dimension, anchor: Anchor, world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
-    let sel_bounds = subselection_bounds(world, state);
 
-    let mut query = <(Write<Sprite>, Read<Position>)>::query().filter(component::<Selection>());
-    for (sprite, pos) in query.iter_mut(world) {
-        if state.mode() == Mode::Edit {
-            let pos2d: Position2D = (*pos).into();
-            let rel_bounds = sel_bounds - pos2d;
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        let bounds = sprite.resize_canvas(canvas, anchor);
 
-            if (*sprite).apply_style(style, rel_bounds) {
-                changed = true;
-            }
-        } else if sprite.fill_style(style) {
-            changed = true;
-        }
+        ext_styles_moved(&mut ext_styles, &bounds);
+
+        *pos += *bounds.position();
+        *dim = *bounds.dimension();
+        changed = true;
+    }
+
+    if !changed {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
+    }
+}
+
+fn play(playback: Playback, world: &mut World, state: &mut State) -> bool {
+    let selected = <Read<Selection>>::query().iter(world).count();
+
+    let mut query = <Read<Keyframes>>::query();
+    let length = query.iter(world).map(|k| k.last_tick()).max().unwrap_or_default();
+
+    state.playback = playback;
+    state.playback.all |= selected == 0; // nothing selected, play everything
+    state.playback.start = state.timeline_tick;
+    state.playback.length = length;
+    state.playback.wait = playback_wait(world, state);
+    state.set_mode(Mode::Play);
+
+    false
+}
+
+fn tick(world: &mut World, state: &mut State) -> bool {
+    state.playback.elapsed += state.playback.wait;
+    state.playback.wait = playback_wait(world, state);
+
+    false
+}
+
+// ms until the next played sprite frame or timeline tick changes
+fn playback_wait(world: &mut World, state: &State) -> u64 {
+    let playback = &state.playback;
+    let mut query = <(Read<Sprite>, TryRead<Animation>, TryRead<Selection>)>::query().filter(!component::<Hidden>());
+    let sprites = query
+        .iter(world)
+        .filter(|(_, _, selection)| playback.all || selection.is_some())
+        .filter_map(|(sprite, animation, _)| playback.frame(sprite.frame_count(), animation).1);
+
+    sprites
+        .chain(playback.timeline_wait())
+        .min()
+        .unwrap_or_else(|| playback.frame_ms())
+}
+
+// color indexes the scene color table must not take over or free, including
+// those of history states since undo and redo bring their texels back
+fn used_colors(world: &mut World, state: &State, resources: &Resources) -> BTreeSet<u8> {
+    let palette = resources.get::<ColorPalette>().unwrap();
+
+    <Read<Sprite>>::query()
+        .iter(world)
+        .flat_map(|sprite| sprite.frames.iter().flatten())
+        .flat_map(|t| [t.bg, t.fg])
+        .chain(palette.used_colors())
+        .chain([state.color(ColorMode::Bg), state.color(ColorMode::Fg)])
+        .chain(state.history_colors())
+        .collect()
+}
+
+fn set_rgb(rgb: Rgb, target: RgbTarget, world: &mut World, state: &mut State, resources: &mut Resources) -> bool {
+    let result = match target {
+        RgbTarget::Color(cm) => {
+            let used = used_colors(world, state, resources);
+            state.set_rgb_color(rgb, cm, &used)
+        }
+        RgbTarget::Palette(index) => {
+            let mut palette = resources.get_mut::<ColorPalette>().unwrap();
+            palette.set_rgb(index, rgb)
+        }
+    };
+
+    if let Err(err) = result {
+        state.set_error(err);
+    }
+
+    false
+}
+
+fn palette(op: PaletteOp, state: &mut State, resources: &mut Resources) -> bool {
+    let mut color_palette = resources.get_mut::<ColorPalette>().unwrap();
+    let mut symbol_palette = resources.get_mut::<SymbolPalette>().unwrap();
+
+    let result = match op {
+        PaletteOp::Load(path) => fio::palette_from_file(&path).and_then(|file| {
+            if let Some(symbols) = file.symbols {
+                *symbol_palette = symbols;
+            }
+            color_palette.set_rgb_colors(&file.colors)
+        }),
+        PaletteOp::Save(path) => {
+            let file = PaletteFile {
+                colors: color_palette.rgb_colors(),
+                symbols: Some(symbol_palette.clone()),
+            };
+
+            fio::palette_to_file(&file, &path)
+        }
+    };
+
+    if let Err(err) = result {
+        state.set_error(err);
+    }
+
+    false
+}
+
+fn timeline(change: TimelineChange, world: &mut World, state: &mut State) -> bool {
+    match change {
+        TimelineChange::Key | TimelineChange::Unkey(_) => key_selected(change, world, state),
+        TimelineChange::Scrub(tick, relative) => {
+            let tick = if relative {
+                add_max(state.timeline_tick, tick, usize::MAX).unwrap_or_default()
+            } else {
+                std::cmp::max(0, tick) as usize
+            };
+            state.timeline_tick = tick;
+
+            // keyed objects jump to their interpolated positions
+            let mut changed = false;
+            let mut query = <(Write<Position>, Read<Keyframes>)>::query();
+            for (pos, keyframes) in query.iter_mut(world) {
+                if let Some(at) = keyframes.at(tick) {
+                    changed |= *pos != at;
+                    *pos = at;
+                }
+            }
+
+            changed
+        }
+    }
+}
+
+fn key_selected(change: TimelineChange, world: &mut World, state: &mut State) -> bool {
+    let tick = state.timeline_tick;
+    let mut changes = Vec::new();
+
+    let mut query = <(Entity, Read<Position>, TryRead<Keyframes>)>::query().filter(component::<Selection>());
+    for (entity, pos, keyframes) in query.iter(world) {
+        let mut keyframes = keyframes.cloned().unwrap_or_default();
+
+        match change {
+            TimelineChange::Unkey(true) => keyframes.0.clear(),
+            TimelineChange::Unkey(false) => {
+                keyframes.0.remove(&tick);
+            }
+            _ => {
+                keyframes.0.insert(tick, *pos);
+            }
+        }
+
+        changes.push((*entity, keyframes));
+    }
+
+    if changes.is_empty() {
+        return state.set_error(Error::execution("No objects selected"));
+    }
+
+    for (entity, keyframes) in changes {
+        if let Some(mut entry) = world.entry(entity) {
+            if keyframes.is_empty() {
+                entry.remove_component::<Keyframes>();
+            } else {
+                entry.add_component(keyframes);
+            }
+        }
+    }
+
+    true
+}
+
+fn hide_selected(world: &mut World, state: &mut State) -> bool {
+    let mut todo = CommandBuffer::new(world);
+    let mut count = 0;
+
+    let mut query = <Entity>::query().filter(component::<Selection>());
+    for entity in query.iter(world) {
+        todo.add_component(*entity, Hidden);
+        todo.remove_component::<Selection>(*entity);
+        count += 1;
+    }
+
+    todo.flush(world, &mut Resources::default());
+
+    if count == 0 {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        true
+    }
+}
+
+fn show_hidden(world: &mut World, state: &mut State) -> bool {
+    let mut todo = CommandBuffer::new(world);
+    let mut count = 0;
+
+    let mut query = <Entity>::query().filter(component::<Hidden>());
+    for entity in query.iter(world) {
+        todo.remove_component::<Hidden>(*entity);
+        count += 1;
+    }
+
+    todo.flush(world, &mut Resources::default());
+
+    if count == 0 {
+        state.set_error(Error::execution("No hidden objects"))
+    } else {
+        true
+    }
+}
+
+fn lock_selected(world: &mut World, state: &mut State) -> bool {
+    let mut todo = CommandBuffer::new(world);
+    let mut count = 0;
+
+    let mut query = <Entity>::query().filter(component::<Selection>());
+    for entity in query.iter(world) {
+        todo.add_component(*entity, Locked);
+        todo.remove_component::<Selection>(*entity);
+        count += 1;
+    }
+
+    todo.flush(world, &mut Resources::default());
+
+    if count == 0 {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        true
+    }
+}
+
+fn unlock_locked(world: &mut World, state: &mut State) -> bool {
+    let mut todo = CommandBuffer::new(world);
+    let mut count = 0;
+
+    let mut query = <Entity>::query().filter(component::<Locked>());
+    for entity in query.iter(world) {
+        todo.remove_component::<Locked>(*entity);
+        count += 1;
+    }
+
+    todo.flush(world, &mut Resources::default());
+
+    if count == 0 {
+        state.set_error(Error::execution("No locked objects"))
+    } else {
+        true
+    }
+}
+
+type Groups = BTreeMap<usize, Option<usize>>;
+
+// group and all of its parents, innermost first
+fn group_chain(group: Option<usize>, groups: &Groups) -> Vec<usize> {
+    let mut result = Vec::new();
+    let mut current = group;
+
+    while let Some(id) = current {
+        if result.contains(&id) {
+            break; // safety against broken files
+        }
+        result.push(id);
+        current = groups.get(&id).copied().flatten();
+    }
+
+    result
+}
+
+// outermost group of object inside the entered group, None if object is a direct child
+fn group_item(group: Option<usize>, entered: Option<usize>, groups: &Groups) -> Option<usize> {
+    group_chain(group, groups)
+        .into_iter()
+        .find(|id| groups.get(id).copied().flatten() == entered)
+}
+
+fn in_group_scope(group: Option<usize>, entered: Option<usize>, groups: &Groups) -> bool {
+    match entered {
+        Some(id) => group_chain(group, groups).contains(&id),
+        None => true,
+    }
+}
+
+fn set_groups(groups: Groups, world: &mut World) {
+    let mut todo = CommandBuffer::new(world);
+    let mut query = <(Entity, Read<GroupInfo>)>::query();
+    for (entity, _) in query.iter(world) {
+        todo.remove(*entity);
+    }
+    todo.flush(world, &mut Resources::default());
+
+    world.extend(groups.into_iter().map(|(id, parent)| (GroupInfo { id, parent },)));
+}
+
+// only objects inside the entered group can be selected
+fn apply_group_scope(world: &mut World, state: &mut State) {
+    let groups = world_groups(world);
+    if let Some(id) = state.entered_group {
+        if !groups.contains_key(&id) {
+            state.entered_group = None; // group is gone (undo, load)
+        }
+    }
+
+    let mut todo = CommandBuffer::new(world);
+    let mut query = <(Entity, TryRead<Group>, TryRead<Selectable>)>::query().filter(component::<Sprite>());
+    for (entity, group, selectable) in query.iter(world) {
+        let in_scope = in_group_scope(group.map(|g| g.0), state.entered_group, &groups);
+
+        if in_scope && selectable.is_none() {
+            todo.add_component(*entity, Selectable);
+        } else if !in_scope && selectable.is_some() {
+            todo.remove_component::<Selectable>(*entity);
+            todo.remove_component::<Selection>(*entity);
+        }
+    }
+
+    todo.flush(world, &mut Resources::default());
+}
+
+// selecting a group member selects the whole group
+fn select_group_members(world: &mut World, state: &State) {
+    let groups = world_groups(world);
+    let mut items = Vec::new();
+
+    let mut query = <Read<Group>>::query().filter(component::<Selection>());
+    for group in query.iter(world) {
+        if let Some(item) = group_item(Some(group.0), state.entered_group, &groups) {
+            items.push(item);
+        }
+    }
+
+    if items.is_empty() {
+        return;
+    }
+
+    let mut todo = CommandBuffer::new(world);
+    let mut query = <(Entity, Read<Group>)>::query().filter(
+        component::<Selectable>() & !component::<Selection>() & !component::<Locked>() & !component::<Hidden>(),
+    );
+    for (entity, group) in query.iter(world) {
+        if let Some(item) = group_item(Some(group.0), state.entered_group, &groups) {
+            if items.contains(&item) {
+                todo.add_component(*entity, Selection);
+            }
+        }
+    }
+
+    todo.flush(world, &mut Resources::default());
+}
+
+fn group_selected(world: &mut World, state: &mut State) -> bool {
+    let mut groups = world_groups(world);
+    let id = groups.keys().next_back().map_or(0, |last| last + 1);
+    let mut items = Vec::new();
+
+    let mut query = <(Entity, TryRead<Group>)>::query().filter(component::<Selection>());
+    for (entity, group) in query.iter(world) {
+        items.push((*entity, group_item(group.map(|g| g.0), state.entered_group, &groups)));
+    }
+
+    if items.is_empty() {
+        return state.set_error(Error::execution("No objects selected"));
+    }
+
+    for (entity, item) in items.into_iter() {
+        match item {
+            Some(child_id) => {
+                groups.insert(child_id, Some(id));
+            }
+            None => {
+                if let Some(mut entry) = world.entry(entity) {
+                    entry.add_component(Group(id));
+                }
+            }
+        }
+    }
+
+    groups.insert(id, state.entered_group);
+    set_groups(groups, world);
+
+    true
+}
+
+fn ungroup_selected(world: &mut World, state: &mut State) -> bool {
+    let mut groups = world_groups(world);
+    let mut items = Vec::new();
+
+    let mut query = <Read<Group>>::query().filter(component::<Selection>());
+    for group in query.iter(world) {
+        if let Some(item) = group_item(Some(group.0), state.entered_group, &groups) {
+            if !items.contains(&item) {
+                items.push(item);
+            }
+        }
+    }
+
+    if items.is_empty() {
+        return state.set_error(Error::execution("No groups selected"));
+    }
+
+    let mut todo = CommandBuffer::new(world);
+    let mut query = <(Entity, Read<Group>)>::query();
+    for (entity, group) in query.iter(world) {
+        if items.contains(&group.0) {
+            // members move to the parent, which is the entered group
+            match state.entered_group {
+                Some(parent) => todo.add_component(*entity, Group(parent)),
+                None => todo.remove_component::<Group>(*entity),
+            }
+        }
+    }
+    todo.flush(world, &mut Resources::default());
+
+    for id in items.iter() {
+        groups.remove(id);
+    }
+    for parent in groups.values_mut() {
+        if parent.is_some_and(|p| items.contains(&p)) {
+            *parent = state.entered_group;
+        }
+    }
+    set_groups(groups, world);
+
+    true
+}
+
+fn enter_group(world: &mut World, state: &mut State) -> bool {
+    let groups = world_groups(world);
+    let mut items = Vec::new();
+
+    let mut query = <TryRead<Group>>::query().filter(component::<Selection>());
+    for group in query.iter(world) {
+        let item = group_item(group.map(|g| g.0), state.entered_group, &groups);
+        if !items.contains(&item) {
+            items.push(item);
+        }
+    }
+
+    match items.as_slice() {
+        [Some(id)] => {
+            state.entered_group = Some(*id);
+            deselect_obj(world);
+            apply_group_scope(world, state);
+            false
+        }
+        _ => state.set_error(Error::execution("Select a single group to enter")),
+    }
+}
+
+fn leave_group(world: &mut World, state: &mut State) -> bool {
+    let id = match state.entered_group {
+        Some(id) => id,
+        None => return state.set_error(Error::execution("Not inside a group")),
+    };
+
+    let groups = world_groups(world);
+    state.entered_group = groups.get(&id).copied().flatten();
+    deselect_obj(world);
+    apply_group_scope(world, state);
+
+    // keep the group we left selected
+    let mut todo = CommandBuffer::new(world);
+    let mut query = <(Entity, Read<Group>)>::query().filter(!component::<Locked>() & !component::<Hidden>());
+    for (entity, group) in query.iter(world) {
+        if group_chain(Some(group.0), &groups).contains(&id) {
+            todo.add_component(*entity, Selection);
+        }
+    }
+    todo.flush(world, &mut Resources::default());
+
+    false
+}
+
+fn apply_layout_to_selected(layout: Layout, world: &mut World, state: &mut State) -> bool {
+    use rand::Rng;
+    let mut rng = rand::rng();
+
+    let bounds = viewport_bounds(state);
+    let mut query =
+        <(Write<Position>, Read<Dimension>)>::query().filter(component::<Selection>() & !component::<Locked>());
+    let mut objects: Vec<(&mut Position, Dimension)> = query.iter_mut(world).map(|(pos, dim)| (pos, *dim)).collect();
+
+    if objects.is_empty() {
+        return false;
+    }
+
+    // storage order is arbitrary, use reading order instead
+    objects.sort_by_key(|(pos, _)| (pos.y, pos.x));
+
+    match layout {
+        Layout::None => false,
+        Layout::Column(cols, padding) => layout_cells(&mut objects, cols, padding, false),
+        Layout::Grid(cols, padding) => layout_cells(&mut objects, cols, padding, true),
+        Layout::Row(padding) => {
+            let cols = objects.len();
+            layout_cells(&mut objects, cols, (padding, 0), false)
+        }
+        Layout::Align(alignment, to_viewport) => {
+            let area = if to_viewport { bounds } else { objects_bounds(&objects) };
+            align_objects(&mut objects, alignment, area)
+        }
+        Layout::Distribute(horizontal, distribution) => distribute_objects(&mut objects, horizontal, distribution),
+        Layout::Random => {
+            let mut changed = false;
+
+            for (pos, dim) in objects.iter_mut() {
+                let bounds_x = bounds.position().x;
+                let bounds_y = bounds.position().y;
+                let bounds_w = i32::from(bounds.dimension().w);
+                let bounds_h = i32::from(bounds.dimension().h);
+                let dim_w = i32::from(dim.w);
+                let dim_h = i32::from(dim.h);
+
+                if dim_w < bounds_w && dim_h < bounds_h {
+                    let x: i32 = rng.random_range(bounds_x..bounds_x + bounds_w - dim_w);
+                    let y: i32 = rng.random_range(bounds_y..bounds_y + bounds_h - dim_h);
+                    changed |= move_to(pos, x, y);
+                }
+            }
+
+            changed
+        }
+    }
+}
+
+// returns if position changed
+fn move_to(pos: &mut Position, x: i32, y: i32) -> bool {
+    let changed = pos.x != x || pos.y != y;
+    pos.x = x;
+    pos.y = y;
+
+    changed
+}
+
+// each column is as wide as its widest object and each row as tall as its tallest,
+// uniform makes all cells the size of the biggest object
+fn layout_cells(objects: &mut [(&mut Position, Dimension)], cols: usize, padding: (u16, u16), uniform: bool) -> bool {
+    let rows = objects.len().div_ceil(cols);
+    let mut col_sizes = vec![0i32; cols];
+    let mut row_sizes = vec![0i32; rows];
+    let start_x = objects.iter().map(|(pos, _)| pos.x).min().unwrap_or_default();
+    let start_y = objects.iter().map(|(pos, _)| pos.y).min().unwrap_or_default();
+
+    for (i, (_, dim)) in objects.iter().enumerate() {
+        col_sizes[i % cols] = std::cmp::max(col_sizes[i % cols], i32::from(dim.w));
+        row_sizes[i / cols] = std::cmp::max(row_sizes[i / cols], i32::from(dim.h));
+    }
+
+    if uniform {
+        let cell_w = col_sizes.iter().copied().max().unwrap_or_default();
+        let cell_h = row_sizes.iter().copied().max().unwrap_or_default();
+        col_sizes.iter_mut().for_each(|w| *w = cell_w);
+        row_sizes.iter_mut().for_each(|h| *h = cell_h);
+    }
+
+    let col_offsets = cell_offsets(&col_sizes, i32::from(padding.0));
+    let row_offsets = cell_offsets(&row_sizes, i32::from(padding.1));
+
+    let mut changed = false;
+    for (i, (pos, _)) in objects.iter_mut().enumerate() {
+        changed |= move_to(pos, start_x + col_offsets[i % cols], start_y + row_offsets[i / cols]);
+    }
+
+    changed
+}
+
+fn cell_offsets(sizes: &[i32], padding: i32) -> Vec<i32> {
+    let mut offset = 0;
+
+    sizes
+        .iter()
+        .map(|size| {
+            let result = offset;
+            offset += size + padding;
+            result
+        })
+        .collect()
+}
+
+fn objects_bounds(objects: &[(&mut Position, Dimension)]) -> Bounds {
+    let left = objects.iter().map(|(pos, _)| pos.x).min().unwrap_or_default();
+    let top = objects.iter().map(|(pos, _)| pos.y).min().unwrap_or_default();
+    let right = objects
+        .iter()
+        .map(|(pos, dim)| pos.x + i32::from(dim.w))
+        .max()
+        .unwrap_or_default();
+    let bottom = objects
+        .iter()
+        .map(|(pos, dim)| pos.y + i32::from(dim.h))
+        .max()
+        .unwrap_or_default();
+
+    Bounds::Free(
+        Position2D::from_xy(left, top),
+        Dimension::from_wh((right - left) as u16, (bottom - top) as u16),
+    )
+}
+
+fn align_objects(objects: &mut [(&mut Position, Dimension)], alignment: Alignment, area: Bounds) -> bool {
+    let left = area.position().x;
+    let top = area.position().y;
+    let area_w = i32::from(area.dimension().w);
+    let area_h = i32::from(area.dimension().h);
+
+    let mut changed = false;
+    for (pos, dim) in objects.iter_mut() {
+        let w = i32::from(dim.w);
+        let h = i32::from(dim.h);
+
+        let (x, y) = match alignment {
+            Alignment::Left => (left, pos.y),
+            Alignment::Right => (left + area_w - w, pos.y),
+            Alignment::Top => (pos.x, top),
+            Alignment::Bottom => (pos.x, top + area_h - h),
+            Alignment::CenterH => (left + (area_w - w) / 2, pos.y),
+            Alignment::CenterV => (pos.x, top + (area_h - h) / 2),
+        };
+        changed |= move_to(pos, x, y);
+    }
+
+    changed
+}
+
+fn distribute_objects(
+    objects: &mut [(&mut Position, Dimension)],
+    horizontal: bool,
+    distribution: Distribution,
+) -> bool {
+    // start and size along the distribution axis
+    let axis = |pos: &Position, dim: &Dimension| {
+        if horizontal {
+            (pos.x, i32::from(dim.w))
+        } else {
+            (pos.y, i32::from(dim.h))
+        }
+    };
+
+    objects.sort_by_key(|(pos, dim)| axis(pos, dim).0);
+
+    let count = objects.len() as i32;
+    let first = axis(objects[0].0, &objects[0].1).0;
+    let last_end = objects
+        .iter()
+        .map(|(pos, dim)| axis(pos, dim))
+        .map(|(start, size)| start + size)
+        .max()
+        .unwrap_or(first);
+    let total_size: i32 = objects.iter().map(|(pos, dim)| axis(pos, dim).1).sum();
+    let mut preceding_size = 0;
+    let mut changed = false;
+
+    for (i, (pos, dim)) in objects.iter_mut().enumerate() {
+        let i = i as i32;
+        let start = match distribution {
+            Distribution::Even if count > 1 => {
+                first + preceding_size + (last_end - first - total_size) * i / (count - 1)
+            }
+            Distribution::Even => first,
+            Distribution::Spacing(spacing) => first + preceding_size + i32::from(spacing) * i,
+            Distribution::Pitch(pitch) => first + i32::from(pitch) * i,
+        };
+        preceding_size += axis(pos, dim).1;
+
+        changed |= if horizontal {
+            move_to(pos, start, pos.y)
+        } else {
+            move_to(pos, pos.x, start)
+        };
+    }
+
+    changed
+}
+
+fn change_frame_on_selected(which: Which<usize>, world: &mut World, state: &mut State) -> bool {
+    if <Read<Selection>>::query().iter(world).count() == 0 {
+        return state.set_error(Error::execution("No objects selected"));
+    }
+
+    let mut changed = false;
+    let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
+    for sprite in query.iter_mut(world) {
+        sprite.apply_frame_change(which);
+        changed = true;
+    }
+
+    changed
+}
+
+fn apply_style_to_selected(style: SymbolStyle, world: &mut World, state: &mut State) -> bool {
+    let mut changed = false;
+    let areas = edit_areas(world, state);
+
+    let mut query = <(Write<Sprite>, Read<Position>, Read<Dimension>)>::query().filter(component::<Selection>());
+    for (sprite, pos, dim) in query.iter_mut(world) {
+        if state.mode() == Mode::Edit {
+            let pos2d: Position2D = (*pos).into();
+
+            for area in areas.iter() {
+                if (*sprite).apply_style(style, *area - pos2d) {
+                    changed = true;
+                }
+            }
+
+            for (mirror_pos, _, _) in mirrored_positions(&areas, pos2d, *dim, state) {
+                sprite.apply_style(style, Bounds::point(mirror_pos - pos2d));
+            }
+        } else if sprite.fill_style(style) {
+            changed = true;
+        }
     }
 
     if state.mode() == Mode::Edit && changed {
@@ -810,21 +1854,92 @@ fn apply_style_to_selected(style: SymbolStyle, world: &mut World, state: &mut St
     changed
 }
 
+fn apply_ext_style_to_selected(style: ExtStyle, world: &mut World, state: &mut State) -> bool {
+    let mut changes = Vec::new();
+    let areas = edit_areas(world, state);
+    let edit = state.mode() == Mode::Edit;
+
+    let mut query = <(
+        Entity,
+        Read<Sprite>,
+        Read<Position>,
+        Read<Dimension>,
+        TryRead<ExtStyleMap>,
+    )>::query()
+    .filter(component::<Selection>());
+    for (entity, sprite, pos, dim, ext_styles) in query.iter(world) {
+        let pos2d: Position2D = (*pos).into();
+        let mut local_areas: Vec<Bounds> = mirrored_positions(&areas, pos2d, *dim, state)
+            .into_iter()
+            .map(|(mirror_pos, _, _)| Bounds::point(mirror_pos - pos2d))
+            .collect();
+        local_areas.extend(areas.iter().map(|area| *area - pos2d));
+
+        let mut ext_styles = ext_styles.cloned().unwrap_or_default();
+        let mut changed = false;
+        for texel in sprite.frame_iter() {
+            if edit && !local_areas.iter().any(|area| area.contains(texel.pos)) {
+                continue;
+            }
+
+            ext_styles.toggle(sprite.index, texel.pos, style);
+            changed = true;
+        }
+
+        if changed {
+            changes.push((*entity, ext_styles));
+        }
+    }
+
+    let changed = !changes.is_empty();
+    for (entity, ext_styles) in changes {
+        if let Some(mut entry) = world.entry(entity) {
+            if ext_styles.is_empty() {
+                entry.remove_component::<ExtStyleMap>();
+            } else {
+                entry.add_component(ext_styles);
+            }
+        }
+    }
+
+    if edit && changed {
+        clear_subselection(world);
+    }
+
+    changed
+}
+
 fn apply_symbol_to_selected(symbol: char, world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
     let bg = state.color(ColorMode::Bg);
     let fg = state.color(ColorMode::Fg);
-    let sel_bounds = subselection_bounds(world, state);
+    let areas = edit_areas(world, state);
 
-    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>)>::query().filter(component::<Selection>());
-    for (sprite, pos, dim) in query.iter_mut(world) {
-        let pos2d: Position2D = (*pos).into();
-        let rel_bounds = sel_bounds - pos2d;
-        let bounds = (*sprite).apply_symbol(symbol, bg, fg, rel_bounds);
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    for (sprite, pos, dim, mut ext_styles) in query.iter_mut(world) {
+        let mirrored = mirrored_positions(&areas, (*pos).into(), *dim, state);
 
-        // changed pos or dim => apply new bounds
-        *pos += *bounds.position();
-        *dim = *bounds.dimension();
+        for area in areas.iter() {
+            let pos2d: Position2D = (*pos).into();
+            let bounds = (*sprite).apply_symbol(symbol, bg, fg, *area - pos2d);
+
+            // changed pos or dim => apply new bounds
+            ext_styles_moved(&mut ext_styles, &bounds);
+            *pos += *bounds.position();
+            *dim = *bounds.dimension();
+        }
+
+        for (mirror_pos, flip_x, flip_y) in mirrored {
+            let pos2d: Position2D = (*pos).into();
+            let mirror_bounds = Bounds::point(mirror_pos - pos2d);
+            let bounds = sprite.apply_symbol(mirror_symbol(symbol, flip_x, flip_y), bg, fg, mirror_bounds);
+
+            ext_styles_moved(&mut ext_styles, &bounds);
+
+            *pos += *bounds.position();
+            *dim = *bounds.dimension();
+        }
 
         changed = true;
     }
@@ -836,6 +1951,25 @@ fn apply_symbol_to_selected(symbol: char, world: &mut World, state: &mut State)
     changed
 }
 
+// absolute positions mirroring given areas if symmetry is on
+fn mirrored_positions(
+    areas: &[Bounds],
+    pos: Position2D,
+    dim: Dimension,
+    state: &State,
+) -> Vec<(Position2D, bool, bool)> {
+    match state.symmetry() {
+        Some(symmetry) => match areas {
+            [area] => symmetry.mirror_area(*area, Bounds::Free(pos, dim)),
+            _ => {
+                let positions: Vec<Position2D> = areas.iter().flat_map(|a| a.into_iter()).collect();
+                symmetry.mirror_positions(&positions, Bounds::Free(pos, dim))
+            }
+        },
+        None => Vec::new(),
+    }
+}
+
 fn clipboard(op: ClipboardOp, world: &mut World, state: &mut State) -> bool {
     match (state.mode(), op) {
         (Mode::Edit, ClipboardOp::Copy) => copy_or_cut_subselection(op, world, state),
@@ -879,7 +2013,14 @@ fn paste_selection(world: &mut World, state: &mut State) -> bool {
 
     deselect_obj(world);
     for sprite in sprites.into_iter() {
-        if match import_sprite(sprite, None, true, world, state) {
+        if match import_sprite(
+            sprite,
+            None,
+            true,
+            ObjectInfo::in_group(state.entered_group),
+            world,
+            state,
+        ) {
             Ok(_) => true,
             Err(err) => state.set_error(err),
         } {
@@ -893,24 +2034,30 @@ fn paste_selection(world: &mut World, state: &mut State) -> bool {
 fn copy_or_cut_subselection(op: ClipboardOp, world: &mut World, state: &mut State) -> bool {
     let mut changed = false;
     let mut found = false;
-    let sel_bounds = subselection_bounds(world, state);
+    let mask = selection_mask(world);
+    let areas = edit_areas(world, state);
 
-    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>)>::query().filter(component::<Selection>());
-    if let Some((sprite, pos, dim)) = query.iter_mut(world).next() {
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    if let Some((sprite, pos, dim, mut ext_styles)) = query.iter_mut(world).next() {
         let pos2d: Position2D = (*pos).into();
-        let rel_bounds = sel_bounds - pos2d;
 
-        state.clipboard = Clipboard::Texels(sprite.copy_area(rel_bounds));
+        state.clipboard = Clipboard::Texels(match &mask {
+            Some(mask) => copy_mask(sprite, mask, pos2d),
+            None => sprite.copy_area(areas[0] - pos2d),
+        });
 
         if op == ClipboardOp::Cut {
-            changed = match sprite.clear_symbol(rel_bounds) {
-                None => false, // no change, symbol was applied in bounds
-                Some(bounds) => {
+            for area in areas.iter() {
+                let pos2d: Position2D = (*pos).into();
+
+                if let Some(bounds) = sprite.clear_symbol(*area - pos2d) {
                     // changed pos or dim => apply new bounds
+                    ext_styles_moved(&mut ext_styles, &bounds);
                     *pos += *bounds.position();
                     *dim = *bounds.dimension();
 
-                    true
+                    changed = true;
                 }
             }
         }
@@ -931,13 +2078,15 @@ fn paste_subselection(world: &mut World, state: &mut State) -> bool {
 
     let mut changed = false;
 
-    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>)>::query().filter(component::<Selection>());
-    if let Some((sprite, pos, dim)) = query.iter_mut(world).next() {
+    let mut query = <(Write<Sprite>, Write<Position>, Write<Dimension>, TryWrite<ExtStyleMap>)>::query()
+        .filter(component::<Selection>());
+    if let Some((sprite, pos, dim, mut ext_styles)) = query.iter_mut(world).next() {
         let texels: Texels = state.clipboard.clone().into();
         let pos2d: Position2D = (*pos).into();
         let rel_pos = state.cursor + state.offset() - pos2d;
 
         let bounds = sprite.apply_texels(texels, rel_pos);
+        ext_styles_moved(&mut ext_styles, &bounds);
         *pos += *bounds.position();
         *dim = *bounds.dimension();
 
@@ -965,11 +2114,49 @@ fn set_metadata(mt: MetadataType, world: &mut World, state: &mut State) -> bool
         return state.set_error(Error::execution("Can only set ID on single object"));
     }
 
+    if mt.is_animation() {
+        return set_animation_metadata(mt, world, state);
+    }
+
     let mut query = <Write<Sprite>>::query().filter(component::<Selection>());
     for sprite in query.iter_mut(world) {
         match &mt {
             MetadataType::Id(id) => sprite.id = *id,
             MetadataType::Labels(labels) => sprite.labels.extend(labels.iter().map(|(k, v)| (k.clone(), v.clone()))),
+            _ => {}
+        }
+    }
+
+    true
+}
+
+fn set_animation_metadata(mt: MetadataType, world: &mut World, state: &mut State) -> bool {
+    let mut query = <(Entity, Read<Sprite>, TryRead<Animation>)>::query().filter(component::<Selection>());
+    let mut changes = Vec::new();
+
+    for (entity, sprite, animation) in query.iter(world) {
+        let mut animation = animation.cloned().unwrap_or_default();
+
+        match &mt {
+            MetadataType::Duration(duration) => animation.set_duration(sprite.index, *duration),
+            MetadataType::Loop(loop_mode) => animation.loop_mode = *loop_mode,
+            MetadataType::Range(name, range) => {
+                if let Some((_, to)) = range {
+                    if *to >= sprite.frame_count() {
+                        return state.set_error(Error::execution("Frame range out of bounds"));
+                    }
+                }
+                animation.set_range(name, *range);
+            }
+            _ => {}
+        }
+
+        changes.push((*entity, animation));
+    }
+
+    for (entity, animation) in changes {
+        if let Some(mut entry) = world.entry(entity) {
+            entry.add_component(animation);
         }
     }
 
@@ -980,35 +2167,38 @@ fn new_sprite(world: &mut World, state: &State, pos: Option<Position>) -> bool {
     deselect_obj(world);
 
     let sprite = Sprite::default();
-    let dim = Dimension::for_sprite(&sprite);
 
-    world.extend(vec![(
-        Selectable,
-        Selection,
-        pos.unwrap_or(NEW_POSITION + state.offset()),
-        dim,
+    import_sprite(
         sprite,
-    )]);
-
-    true
+        pos,
+        true,
+        ObjectInfo::in_group(state.entered_group),
+        world,
+        state,
+    )
+    .is_ok()
 }
 
 fn duplicate_selected(count: usize, world: &mut World, state: &mut State) -> bool {
     let mut done = 0;
-    let mut query = <(Read<Sprite>, Read<Position>)>::query().filter(component::<Selection>());
+    let mut query = <(Read<Sprite>, Read<Position>, TryRead<ExtStyleMap>)>::query().filter(component::<Selection>());
     let mut clones = Vec::new();
 
     for i in 0..count {
         let iteration = (i * 2) as i32;
-        for (sprite, pos) in query.iter(world) {
-            clones.push(((*sprite).clone(), Some(*pos + 2 + iteration)));
+        for (sprite, pos, ext_styles) in query.iter(world) {
+            clones.push(((*sprite).clone(), Some(*pos + 2 + iteration), ext_styles.cloned()));
         }
     }
 
     deselect_obj(world);
 
-    for (sprite, pos) in clones.into_iter() {
-        let import_result = import_sprite(sprite, pos, true, world, state);
+    for (sprite, pos, ext_styles) in clones.into_iter() {
+        let info = ObjectInfo {
+            ext_styles: ext_styles.unwrap_or_default(),
+            ..ObjectInfo::in_group(state.entered_group)
+        };
+        let import_result = import_sprite(sprite, pos, true, info, world, state);
         match import_result {
             Ok(_) => done += 1,
             Err(err) => return state.set_error(err),
@@ -1022,53 +2212,276 @@ fn import_sprite(
     sprite: Sprite,
     pos: Option<Position>,
     pre_select: bool,
+    info: ObjectInfo,
     world: &mut World,
     state: &State,
 ) -> Result<(), Error> {
-    if pre_select {
-        world.extend(vec![(
-            Selectable,
-            Selection,
-            pos.unwrap_or(NEW_POSITION + state.offset()),
-            Dimension::for_sprite(&sprite),
-            sprite,
-        )]);
+    let entities = if pre_select {
+        world
+            .extend(vec![(
+                Selectable,
+                Selection,
+                pos.unwrap_or(NEW_POSITION + state.offset()),
+                Dimension::for_sprite(&sprite),
+                sprite,
+            )])
+            .to_vec()
     } else {
-        world.extend(vec![(
-            Selectable,
-            pos.unwrap_or(NEW_POSITION + state.offset()),
-            Dimension::for_sprite(&sprite),
-            sprite,
-        )]);
+        world
+            .extend(vec![(
+                Selectable,
+                pos.unwrap_or(NEW_POSITION + state.offset()),
+                Dimension::for_sprite(&sprite),
+                sprite,
+            )])
+            .to_vec()
+    };
+
+    for entity in entities {
+        if let Some(mut entry) = world.entry(entity) {
+            if let Some(id) = info.group {
+                entry.add_component(Group(id));
+            }
+            if info.hidden {
+                entry.add_component(Hidden);
+            }
+            if info.locked {
+                entry.add_component(Locked);
+            }
+            if let Some(animation) = info.animation.clone() {
+                entry.add_component(animation);
+            }
+            if !info.keyframes.is_empty() {
+                entry.add_component(info.keyframes.clone());
+            }
+            if !info.ext_styles.is_empty() {
+                entry.add_component(info.ext_styles.clone());
+            }
+        }
     }
 
     Ok(())
 }
 
+// scene as saved, including editor state kept with it
+pub(super) fn scene_to_save(world: &mut World, state: &State) -> Scene {
+    let mut scene = Scene::from_world(world).current();
+    scene.grid = state.grid;
+    scene.guides = state.guides.clone();
+    scene.colors = state.colors.clone();
+
+    Scene::V3(scene)
+}
+
 fn save_scene(new_path: &Option<String>, world: &mut World, state: &mut State) -> Result<(), Error> {
     let path = state.save_file(new_path)?;
-    let scene = Scene::from_world(world);
+    let scene = scene_to_save(world, state);
+
+    fio::scene_to_file(&scene, &path)?;
+    if let Some(history) = state.history_to_save() {
+        fio::history_to_file(&history, &path)?;
+    }
+
+    // an earlier session's swap stays until recovered or discarded
+    if state.stale_swap() {
+        return Ok(());
+    }
+
+    // saved changes don't need recovery, saving under a new name leaves the old swap behind too
+    discard_swap(state);
+    fio::remove_swap_file(&path)
+}
+
+fn discard_swap(state: &State) {
+    if let Ok(path) = state.save_file(&None) {
+        fio::remove_swap_file(&path).unwrap_or_default(); // best effort
+    }
+}
+
+fn recover(discard: bool, world: &mut World, state: &mut State) -> bool {
+    let path = match state.save_file(&None) {
+        Ok(path) => path,
+        Err(err) => return state.set_error(err),
+    };
+
+    if discard {
+        return match fio::remove_swap_file(&path) {
+            Ok(_) => {
+                state.set_stale_swap(false);
+                false
+            }
+            Err(err) => state.set_error(err),
+        };
+    }
 
-    fio::scene_to_file(&scene, &path)
+    match fio::scene_from_swap_file(&path) {
+        Ok(scene) => {
+            state.reset_mode(); // revert to object mode
+            state.entered_group = None;
+            state.grid = scene.grid();
+            state.guides = scene.guides();
+            state.colors = scene.colors();
+            match apply_scene(scene, world, state, None) {
+                Ok(_) => {
+                    state.set_stale_swap(false); // autosave takes over the swap again
+                    true // recovered changes are unsaved, undo goes back to the file
+                }
+                Err(err) => state.set_error(err),
+            }
+        }
+        Err(err) => state.set_error(err),
+    }
 }
 
-fn export_to_file(format: ExportFormat, path: &str, world: &mut World, state: &mut State) -> bool {
-    let scene = Scene::from_world(world);
+fn export_to_file(
+    format: ExportFormat,
+    path: &str,
+    include_hidden: bool,
+    depth: Option<ColorDepth>,
+    world: &mut World,
+    state: &mut State,
+) -> bool {
+    // same scene data as on save so exact colors make it into exports
+    let mut scene = scene_to_save(world, state).current();
+    if !include_hidden {
+        scene.objects.retain(|obj| !obj.2.hidden);
+    }
+
+    // colors the target terminal can't show, exported anyway
+    let outside: BTreeSet<u8> = match depth {
+        Some(depth) => scene
+            .objects
+            .iter()
+            .flat_map(|obj| obj.0.frames.iter().flatten())
+            .flat_map(|t| [t.bg, t.fg])
+            .filter(|c| !depth.fits(*c))
+            .collect(),
+        None => BTreeSet::new(),
+    };
 
-    match fio::export_to_file(scene, format, path) {
+    match fio::export_to_file(Scene::V3(scene), state.timeline_tick, format, path) {
+        Ok(_) if !outside.is_empty() => state.set_error(Error::Execution(format!(
+            "Exported, {} colors outside {} color depth",
+            outside.len(),
+            depth.map(ColorDepth::as_str).unwrap_or_default()
+        ))),
         Ok(_) => false,
         Err(err) => state.set_error(err),
     }
 }
 
+fn remap(op: RemapOp, world: &mut World, state: &mut State, resources: &mut Resources) -> bool {
+    let mut remaps = resources.get_mut::<Remaps>().unwrap();
+
+    let (source, cm, all_frames) = match op {
+        RemapOp::Save(name, remap) => {
+            remaps.0.insert(name, remap);
+            return false;
+        }
+        RemapOp::Delete(name) => {
+            if remaps.0.remove(&name).is_none() {
+                state.set_error(Error::execution("Unknown remap"));
+            }
+            return false;
+        }
+        RemapOp::Apply(source, cm, all_frames) => (source, cm, all_frames),
+    };
+
+    let remap = match source {
+        RemapSource::Inline(remap) => remap,
+        RemapSource::Named(name) => match remaps.0.get(&name) {
+            Some(remap) => remap.clone(),
+            None => return state.set_error(Error::execution("Unknown remap")),
+        },
+    };
+
+    let used = used_colors(world, state, resources);
+    let mapping = match remap.resolve(&mut state.colors, &used) {
+        Ok(mapping) => mapping,
+        Err(err) => return state.set_error(err),
+    };
+    let map = |color: &mut u8| match mapping.get(color) {
+        Some(target) if target != color => {
+            *color = *target;
+            true
+        }
+        _ => false,
+    };
+
+    let mut changed = false;
+    let mut selected = 0;
+    let mut query = <Write<Sprite>>::query().filter(component::<Selection>() & !component::<Locked>());
+    for sprite in query.iter_mut(world) {
+        selected += 1;
+
+        let frames = if all_frames {
+            &mut sprite.frames[..]
+        } else {
+            let index = sprite.index;
+            &mut sprite.frames[index..=index]
+        };
+
+        for texel in frames.iter_mut().flatten() {
+            if cm != Some(ColorMode::Fg) {
+                changed |= map(&mut texel.bg);
+            }
+            if cm != Some(ColorMode::Bg) {
+                changed |= map(&mut texel.fg);
+            }
+        }
+    }
+
+    if selected == 0 {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
+    }
+}
+
+// remaps all frames of selected sprites to nearest colors of given depth
+fn quantize_selected(depth: ColorDepth, world: &mut World, state: &mut State) -> bool {
+    let mut changed = false;
+    let mut selected = 0;
+
+    let mut query = <Write<Sprite>>::query().filter(component::<Selection>() & !component::<Locked>());
+    for sprite in query.iter_mut(world) {
+        selected += 1;
+
+        for texel in sprite.frames.iter_mut().flatten() {
+            let (bg, fg) = (
+                depth.quantize(texel.bg, &state.colors),
+                depth.quantize(texel.fg, &state.colors),
+            );
+
+            if bg != texel.bg || fg != texel.fg {
+                texel.bg = bg;
+                texel.fg = fg;
+                changed = true;
+            }
+        }
+    }
+
+    if selected == 0 {
+        state.set_error(Error::execution("No objects selected"))
+    } else {
+        changed
+    }
+}
+
 fn new(force: bool, world: &mut World, state: &mut State) -> bool {
     if !force && state.unsaved_changes() {
         state.set_error(Error::execution("Unsaved changes, save before opening new scene"));
 
         false
     } else {
+        state.entered_group = None;
+        state.timeline_tick = 0;
+        state.grid = Grid::default();
+        state.guides = Guides::default();
+        state.colors = ColorTable::default();
         match apply_scene(Scene::default(), world, state, None) {
             Ok(_) => {
+                discard_swap(state); // changes are gone
                 state.clear_history(Scene::default()); // we're going from this scene now
                 state.reset_save_file(); // ensure we don't save the tutorial into previous file
                 false // new scene does not dirty
@@ -1085,6 +2498,11 @@ fn tutorial(world: &mut World, state: &mut State) -> bool {
         false
     } else {
         use fio::Loaded;
+        state.entered_group = None;
+        state.timeline_tick = 0;
+        state.grid = Grid::default();
+        state.guides = Guides::default();
+        state.colors = ColorTable::default();
         let bytes = include_bytes!("../../help/tutorial.rgz");
         match fio::scene_from_rgz_stream(&bytes[..]) {
             Ok(loaded) => match loaded {
@@ -1134,16 +2552,37 @@ fn load_from_file(path: &str, world: &mut World, state: &mut State) -> Result<bo
             if state.unsaved_changes() {
                 Err(Error::execution("Unsaved changes, save before opening another scene"))
             } else {
+                if state.save_file(&None).ok().as_deref() != Some(path) {
+                    discard_swap(state); // leaving previous scene
+                }
                 state.reset_mode(); // revert to object mode
+                state.entered_group = None;
+                state.timeline_tick = 0;
+                state.grid = scene.grid();
+                state.guides = scene.guides();
+                state.colors = scene.colors();
                 apply_scene(scene.clone(), world, state, None)?;
                 state.clear_history(scene); // we're going from this scene now
+                if state.persistent_undo.is_some() {
+                    // missing or outdated history just starts fresh
+                    if let Ok(saved) = fio::history_from_file(path) {
+                        state.restore_history(saved);
+                    }
+                }
                 state.saved(String::from(path));
                 Ok(false)
             }
         }
         Loaded::Sprite(sprite) => {
             deselect_obj(world);
-            import_sprite(sprite, None, true, world, state)?;
+            import_sprite(
+                sprite,
+                None,
+                true,
+                ObjectInfo::in_group(state.entered_group),
+                world,
+                state,
+            )?;
             Ok(true)
         }
     }
@@ -1152,7 +2591,12 @@ fn load_from_file(path: &str, world: &mut World, state: &mut State) -> Result<bo
 fn clear_scene(world: &mut World) -> Result<(), Error> {
     let mut todo = CommandBuffer::new(world);
 
-    let mut query = <(Entity, Read<Selectable>)>::query();
+    let mut query = <(Entity, Read<Sprite>)>::query();
+    for (entity, _) in query.iter(world) {
+        todo.remove(*entity);
+    }
+
+    let mut query = <(Entity, Read<GroupInfo>)>::query();
     for (entity, _) in query.iter(world) {
         todo.remove(*entity);
     }
@@ -1162,7 +2606,12 @@ fn clear_scene(world: &mut World) -> Result<(), Error> {
     Ok(())
 }
 
-fn apply_scene(scene: Scene, world: &mut World, state: &State, selections: Option<Vec<usize>>) -> Result<(), Error> {
+fn apply_scene(
+    scene: Scene,
+    world: &mut World,
+    state: &mut State,
+    selections: Option<Vec<usize>>,
+) -> Result<(), Error> {
     clear_scene(world)?;
 
     let current = scene.current();
@@ -1170,13 +2619,16 @@ fn apply_scene(scene: Scene, world: &mut World, state: &State, selections: Optio
 
     for (i, obj) in current.objects.into_iter().enumerate() {
         let selected = selections.contains(&i);
-        import_sprite(obj.0, Some(obj.1), selected, world, state)?;
+        import_sprite(obj.0, Some(obj.1), selected, obj.2, world, state)?;
     }
 
     for (index, pos) in current.bookmarks.into_iter() {
         set_bookmark(index, pos, world);
     }
 
+    set_groups(current.groups, world);
+    apply_group_scope(world, state);
+
     Ok(())
 }
 
@@ -1202,6 +2654,31 @@ fn undo(world: &mut World, state: &mut State) -> bool {
     }
 }
 
+// opens or moves selection of the undo tree view
+fn undo_list(id: Option<usize>, state: &mut State) -> bool {
+    if state.show_history().is_some() {
+        state.reverse_mode();
+    }
+
+    let id = id.unwrap_or_else(|| state.history_index());
+    state.set_mode(Mode::History(id));
+
+    false
+}
+
+fn undo_to(id: usize, world: &mut World, state: &mut State) -> bool {
+    state.reverse_mode(); // leave undo tree view
+
+    if let Some(snap) = state.jump_history(id) {
+        match apply_scene(snap.scene, world, state, Some(snap.selections)) {
+            Ok(_) => validate_mode(world, state),
+            Err(err) => state.set_error(err),
+        }
+    } else {
+        state.set_error(Error::execution("Undo state not found"))
+    }
+}
+
 fn redo(world: &mut World, state: &mut State) -> bool {
     if let Some(snap) = state.redo() {
         match apply_scene(snap.scene, world, state, Some(snap.selections)) {
@@ -1213,3 +2690,134 @@ fn redo(world: &mut World, state: &mut State) -> bool {
         false
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::common::ColorRemap;
+    use texel_types::{SymbolStyles, Texel, DEFAULT_BG_U8};
+
+    #[test]
+    fn exports_exact_scene_colors() {
+        let mut world = World::default();
+        let mut state = State::default();
+        let index = state.colors.assign(Rgb { r: 1, g: 2, b: 3 }, &BTreeSet::new()).unwrap();
+        let sprite = Sprite::from_texels(vec![Texel {
+            pos: Position2D::default(),
+            symbol: 'x',
+            bg: DEFAULT_BG_U8,
+            fg: index,
+            styles: SymbolStyles::new(),
+        }]);
+        let pos = Position { x: 0, y: 0, z: 0 };
+        import_sprite(sprite, Some(pos), false, ObjectInfo::default(), &mut world, &state).unwrap();
+
+        let path = std::env::temp_dir().join("texel_exports_exact_scene_colors.ans");
+        let path = path.to_str().unwrap();
+        assert!(!export_to_file(
+            ExportFormat::Ansi,
+            path,
+            false,
+            None,
+            &mut world,
+            &mut state
+        ));
+
+        let exported = std::fs::read_to_string(path).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert!(exported.contains("38;2;1;2;3"));
+    }
+
+    #[test]
+    fn keeps_colors_of_undoable_states() {
+        let mut world = World::default();
+        let mut state = State::default();
+        let mut resources = Resources::default();
+        resources.insert(ColorPalette::default());
+
+        let rgb = Rgb { r: 1, g: 2, b: 3 };
+        set_rgb(
+            rgb,
+            RgbTarget::Color(ColorMode::Fg),
+            &mut world,
+            &mut state,
+            &mut resources,
+        );
+        let index = state.color(ColorMode::Fg);
+        let sprite = Sprite::from_texels(vec![Texel {
+            pos: Position2D::default(),
+            symbol: 'x',
+            bg: DEFAULT_BG_U8,
+            fg: index,
+            styles: SymbolStyles::new(),
+        }]);
+        let pos = Position { x: 0, y: 0, z: 0 };
+        import_sprite(sprite, Some(pos), false, ObjectInfo::default(), &mut world, &state).unwrap();
+        state.set_dirty(String::from("Import"));
+        state.push_history(scene_to_save(&mut world, &state), Vec::new());
+
+        clear_scene(&mut world).unwrap();
+        state.set_dirty(String::from("Delete"));
+        state.push_history(scene_to_save(&mut world, &state), Vec::new());
+
+        // fill the table up, only undo still refers to the first color
+        for i in 0..=u8::MAX {
+            set_rgb(
+                Rgb { r: i, g: 255, b: 0 },
+                RgbTarget::Color(ColorMode::Fg),
+                &mut world,
+                &mut state,
+                &mut resources,
+            );
+        }
+        assert_eq!(state.colors.get(index), Some(rgb));
+
+        assert!(!undo(&mut world, &mut state));
+        let fg = <Read<Sprite>>::query().iter(&world).next().unwrap().frames[0][0].fg;
+        assert_eq!(state.colors.get(fg), Some(rgb));
+    }
+
+    #[test]
+    fn keeps_colors_of_undoable_remaps() {
+        let mut world = World::default();
+        let mut state = State::default();
+        let mut resources = Resources::default();
+        resources.insert(ColorPalette::default());
+        resources.insert(Remaps::default());
+
+        let sprite = Sprite::from_texels(vec![Texel {
+            pos: Position2D::default(),
+            symbol: 'x',
+            bg: DEFAULT_BG_U8,
+            fg: 196,
+            styles: SymbolStyles::new(),
+        }]);
+        let pos = Position { x: 0, y: 0, z: 0 };
+        import_sprite(sprite, Some(pos), true, ObjectInfo::default(), &mut world, &state).unwrap();
+        state.set_dirty(String::from("Import"));
+        state.push_history(scene_to_save(&mut world, &state), Vec::new());
+
+        let rgb = Rgb { r: 1, g: 2, b: 3 };
+        let remap_196 = ColorRemap::parse("196=#010203").unwrap();
+        let op = RemapOp::Apply(RemapSource::Inline(remap_196), None, false);
+        assert!(remap(op, &mut world, &mut state, &mut resources));
+        state.set_dirty(String::from("Remap"));
+        state.push_history(scene_to_save(&mut world, &state), Vec::new());
+        assert!(!undo(&mut world, &mut state));
+
+        // fill the table up, only redo still refers to the remapped color
+        for i in 0..=u8::MAX {
+            set_rgb(
+                Rgb { r: i, g: 255, b: 0 },
+                RgbTarget::Color(ColorMode::Fg),
+                &mut world,
+                &mut state,
+                &mut resources,
+            );
+        }
+
+        assert!(!redo(&mut world, &mut state));
+        let fg = <Read<Sprite>>::query().iter(&world).next().unwrap().frames[0][0].fg;
+        assert_eq!(state.colors.get(fg), Some(rgb));
+    }
+}
@@ -0,0 +1,20 @@
+use super::action_handler::scene_to_save;
+use crate::common::fio;
+use crate::resources::State;
+use legion::*;
+
+// writes changes since last save to the swap file once a trigger is hit
+pub fn autosave(world: &mut World, state: &mut State) {
+    if !state.swap_due() {
+        return;
+    }
+
+    // swap is keyed by scene path, unnamed scenes have none
+    if let Ok(path) = state.save_file(&None) {
+        if let Err(err) = fio::scene_to_swap_file(&scene_to_save(world, state), &path) {
+            state.set_error(err);
+        }
+    }
+
+    state.swapped(); // failures retry on next trigger
+}
@@ -1,8 +1,7 @@
-use crate::common::{Scene, SceneExt};
-use crate::components::{Bookmark, Position, Position2D, Selection, Sprite};
+use crate::common::{world_bookmarks, world_groups, Animation, ExtStyleMap, Keyframes, ObjectInfo, Scene, SceneExt};
+use crate::components::{Group, Hidden, Locked, Position, Selection, Sprite};
 use crate::resources::State;
 use legion::*;
-use std::collections::BTreeMap;
 
 pub fn preserve_history(world: &mut World, state: &mut State) {
     if !state.needs_preserving() {
@@ -11,23 +10,34 @@ pub fn preserve_history(world: &mut World, state: &mut State) {
 
     let mut objects = Vec::new();
     let mut selections = Vec::new();
-    let mut bookmarks = BTreeMap::new();
 
-    let mut query = <(Read<Position>, Read<Sprite>, TryRead<Selection>)>::query();
+    let mut query = <(
+        Read<Position>,
+        Read<Sprite>,
+        TryRead<Group>,
+        TryRead<Hidden>,
+        TryRead<Locked>,
+        (TryRead<Animation>, TryRead<Keyframes>, TryRead<ExtStyleMap>), // past 8 views, nest
+        TryRead<Selection>,
+    )>::query();
 
-    for (i, (pos, sprite, selected)) in query.iter(world).enumerate() {
-        objects.push((sprite.clone(), *pos));
+    for (i, (pos, sprite, group, hidden, locked, (animation, keyframes, ext_styles), selected)) in
+        query.iter(world).enumerate()
+    {
+        let info = ObjectInfo {
+            group: group.map(|g| g.0),
+            hidden: hidden.is_some(),
+            locked: locked.is_some(),
+            animation: animation.cloned(),
+            keyframes: keyframes.cloned().unwrap_or_default(),
+            ext_styles: ext_styles.cloned().unwrap_or_default(),
+        };
+        objects.push((sprite.clone(), *pos, info));
         if selected.is_some() {
             selections.push(i);
         }
     }
 
-    let mut query = <(Read<Position2D>, Read<Bookmark>)>::query();
-
-    for (pos, bookmark) in query.iter(world) {
-        bookmarks.insert(bookmark.0, *pos);
-    }
-
-    let scene = Scene::from_objects(objects, bookmarks);
+    let scene = Scene::from_objects(objects, world_bookmarks(world), world_groups(world));
     state.push_history(scene, selections);
 }
@@ -1,4 +1,4 @@
-use crate::common::{Action, Event, InputEvent, Mode, MoveMeta, SelectMode};
+use crate::common::{Action, Event, InputEvent, Mode, MoveMeta, RgbTarget, SelectMode};
 use crate::components::{Direction, Translation};
 use crate::resources::{CmdLine, ColorPalette, State, SymbolPalette};
 use legion::*;
@@ -20,6 +20,8 @@ pub fn handle_input(state: &mut State, resources: &mut Resources) {
             Mode::Edit => edit_event(event, state, symbol_palette),
             Mode::Write => write_event(event, state),
             Mode::Help(_) => help_event(event, state),
+            Mode::Play => play_event(event, state),
+            Mode::History(id) => history_event(event, state, id),
             Mode::Quitting(_) => {}
         }
     }
@@ -67,6 +69,7 @@ fn objmode_event(event: InputEvent, state: &mut State) {
         Event::SwapColor => Action::SwapColor,
         Event::ApplyColor(cm) => Action::ApplyColor(cm),
         Event::ApplyStyle(style) => Action::ApplyStyle(style),
+        Event::ApplyExtStyle(style) => Action::ApplyExtStyle(style),
 
         Event::Left(MoveMeta::Relative) | Event::ArrowLeft => Action::Translate(Translation::Relative(-1, 0, 0)),
         Event::Up(MoveMeta::Relative) | Event::ArrowUp => Action::Translate(Translation::Relative(0, -1, 0)),
@@ -83,6 +86,13 @@ fn objmode_event(event: InputEvent, state: &mut State) {
             Action::SelectObject(Which::At(pos), sticky)
         }
 
+        Event::MouseDrag(pos) if state.grid.snap => {
+            // move by whole cells, keep the leftover for next drag
+            let (cells, rest) = state.grid.cells(pos - state.mouse_entry);
+            state.mouse_entry = pos - rest;
+            Action::Translate(Translation::Relative(cells.x, cells.y, 0))
+        }
+
         Event::MouseDrag(pos) => {
             let diff = pos - state.mouse_entry;
             state.mouse_entry = pos;
@@ -150,16 +160,26 @@ fn cmdline_event(event: InputEvent, state: &mut State, cmdline: &mut CmdLine) {
     };
 }
 
-fn color_event(event: InputEvent, state: &mut State, cm: ColorMode, palette: &ColorPalette) {
+fn color_event(event: InputEvent, state: &mut State, cm: ColorMode, palette: &mut ColorPalette) {
     match event.0 {
         Event::Mode(Mode::Command) => {
             state.push_action(Action::ReverseMode);
             state.push_action(Action::SetMode(Mode::Command));
         }
         Event::SwapColor => state.push_action(Action::SwapColor),
-        Event::EditPalette(index) => state.push_action(Action::SetMode(Mode::SelectColor(index, cm))),
+        Event::EditPalette(index) => {
+            // start the picker on the color being replaced
+            let pos = ColorPalette::color_to_pos(palette.color(index));
+
+            state.push_action(Action::SetMode(Mode::SelectColor(index, cm)));
+            state.push_action(Action::Translate(Translation::Absolute(pos.x, pos.y, None)));
+        }
+        Event::PalettePage(forward) => palette.switch_page(forward),
         Event::SelectPalette(index) => {
-            state.set_color(palette.color(index), cm);
+            match palette.rgb(index) {
+                Some(rgb) => state.push_action(Action::SetRgb(rgb, RgbTarget::Color(cm))),
+                None => state.set_color(palette.color(index), cm),
+            }
             state.push_action(Action::ReverseMode);
         }
         Event::Cancel => state.push_action(Action::Cancel),
@@ -205,11 +225,15 @@ fn write_event(event: InputEvent, state: &mut State) {
     state.push_action(action);
 }
 
-fn edit_event(event: InputEvent, state: &mut State, palette: &SymbolPalette) {
+fn edit_event(event: InputEvent, state: &mut State, palette: &mut SymbolPalette) {
     let action = match event.0 {
         Event::Mode(mode) => Action::SetMode(mode),
         Event::EditPalette(index) => Action::SetMode(Mode::SelectSymbol(index)),
         Event::SelectPalette(index) => Action::ApplySymbol(palette.symbol(index)),
+        Event::PalettePage(forward) => {
+            palette.switch_page(forward);
+            Action::None
+        }
         Event::Clipboard(op) => Action::Clipboard(op),
         Event::ToggleMetadata => Action::ToggleMetadata,
 
@@ -219,6 +243,8 @@ fn edit_event(event: InputEvent, state: &mut State, palette: &SymbolPalette) {
         Event::NewFrame => Action::NewFrame,
         Event::SelectRegion => Action::SelectRegion,
         Event::SelectFrame(which) => Action::SelectFrame(which),
+        Event::ToggleSymmetry => Action::ToggleSymmetry,
+        Event::MagicWand(op) => Action::MagicWand(op),
 
         Event::Undo => Action::Undo,
         Event::Redo => Action::Redo,
@@ -232,6 +258,7 @@ fn edit_event(event: InputEvent, state: &mut State, palette: &SymbolPalette) {
         Event::PickColor(cm) => Action::PickColor(cm),
         Event::ApplyColor(cm) => Action::ApplyColor(cm),
         Event::ApplyStyle(style) => Action::ApplyStyle(style),
+        Event::ApplyExtStyle(style) => Action::ApplyExtStyle(style),
 
         Event::Left(MoveMeta::Relative) | Event::ArrowLeft => Action::Translate(Translation::Relative(-1, 0, 0)),
         Event::Up(MoveMeta::Relative) | Event::ArrowUp => Action::Translate(Translation::Relative(0, -1, 0)),
@@ -271,11 +298,18 @@ fn symbol_select_event(event: InputEvent, state: &mut State, index: usize, palet
 fn color_select_event(event: InputEvent, state: &mut State, index: usize, palette: &mut ColorPalette) {
     let action = match event.0 {
         Event::Cancel => Action::Cancel,
-        Event::Confirm => {
-            if let Err(err) = palette.set_color(index, ColorPalette::pos_to_color(state.cursor)) {
-                state.set_error(err);
+        Event::Confirm => match ColorPalette::pos_to_color(state.cursor) {
+            Some(color) => {
+                if let Err(err) = palette.set_color(index, color) {
+                    state.set_error(err);
+                }
+                Action::ReverseMode
             }
-            Action::ReverseMode
+            None => Action::None, // empty picker cell
+        },
+        Event::PalettePage(forward) => {
+            let pos = ColorPalette::picker_section(state.cursor, forward);
+            Action::Translate(Translation::Absolute(pos.x, pos.y, None))
         }
 
         Event::Left(MoveMeta::Relative) | Event::ArrowLeft => Action::Translate(Translation::Relative(-1, 0, 0)),
@@ -297,6 +331,41 @@ fn color_select_event(event: InputEvent, state: &mut State, index: usize, palett
     state.push_action(action);
 }
 
+fn play_event(event: InputEvent, state: &mut State) {
+    let action = match event.0 {
+        Event::Tick => Action::Tick,
+        Event::Cancel => Action::ReverseMode,
+        Event::Mode(Mode::Command) => Action::SetMode(Mode::Command),
+        _ => Action::None,
+    };
+
+    state.push_action(action);
+}
+
+fn history_event(event: InputEvent, state: &mut State, id: usize) {
+    let list = state.history_list();
+    let row = list.iter().position(|item| item.id == id).unwrap_or_default();
+    let select = |row: usize| {
+        list.get(row)
+            .map_or(Action::None, |item| Action::UndoList(Some(item.id)))
+    };
+
+    let action = match event.0 {
+        Event::Confirm => Action::UndoTo(id),
+        Event::Cancel => Action::ReverseMode,
+        Event::Mode(Mode::Command) => Action::SetMode(Mode::Command),
+
+        Event::Up(MoveMeta::Relative) | Event::ArrowUp => select(row.saturating_sub(1)),
+        Event::Down(MoveMeta::Relative) | Event::ArrowDown => select(row + 1),
+        Event::Up(MoveMeta::ToEdge) => select(0),
+        Event::Down(MoveMeta::ToEdge) => select(list.len().saturating_sub(1)),
+
+        _ => Action::None,
+    };
+
+    state.push_action(action);
+}
+
 fn help_event(event: InputEvent, state: &mut State) {
     let action = match event.0 {
         Event::None => Action::None, // don't shut down help on mouse moves etc.
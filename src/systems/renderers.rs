@@ -1,9 +1,15 @@
 mod cmdline_renderer;
+mod grid_renderer;
+mod history_renderer;
 mod meta_renderer;
+mod ruler_renderer;
 mod sprite_renderer;
 mod subselection_renderer;
 
 pub use cmdline_renderer::render_cmdline;
+pub use grid_renderer::{render_grid, render_guides};
+pub use history_renderer::render_history;
 pub use meta_renderer::render_meta_info;
+pub use ruler_renderer::render_rulers;
 pub use sprite_renderer::render_sprites;
 pub use subselection_renderer::render_subselections;
@@ -32,7 +32,8 @@ pub fn render_cmdline(state: &State, resources: &Resources, out: &mut FrameBuffe
     match mode {
         Mode::Quitting(_) => {}
         Mode::Command => print_cmdline(out, &cmdline, h),
-        Mode::Object(_) | Mode::Help(_) => print_mode(out, state, mode, w, h),
+        Mode::Object(_) | Mode::Help(_) | Mode::History(_) => print_mode(out, state, mode, w, h),
+        Mode::Play => print_play(out, state, w, h),
         Mode::Write => print_write(out, state, h),
         Mode::Edit => print_edit(out, state, &symbol_palette, h),
         Mode::Color(cm) => print_color_select(out, state, &color_palette, cm, w, h),
@@ -73,15 +74,96 @@ fn print_write(out: &mut FrameBuffer, state: &State, h: i32) {
     let text = format!("--{}--", state.mode().as_str());
 
     out.write_line(0, h - 1, text, texel_types::DEFAULT_BG_U8, white, bold);
+    print_symmetry(out, state, 10, h);
     out.set_cursor_pos(state.cursor.x, state.cursor.y);
 }
 
+fn print_symmetry(out: &mut FrameBuffer, state: &State, x: i32, h: i32) {
+    if let Some(symmetry) = state.symmetry() {
+        let gray = Terminal::grayscale_u8(12);
+
+        out.write_line(
+            x,
+            h - 1,
+            symmetry.as_str(),
+            texel_types::DEFAULT_BG_U8,
+            gray,
+            SymbolStyles::new(),
+        );
+    }
+}
+
+// returns width used
+fn print_group(out: &mut FrameBuffer, state: &State, x: i32, h: i32) -> i32 {
+    if let Some(id) = state.entered_group {
+        let gray = Terminal::grayscale_u8(12);
+        let text = format!("[G{}]", id);
+        let width = text.len() as i32 + 1;
+
+        out.write_line(x, h - 1, text, texel_types::DEFAULT_BG_U8, gray, SymbolStyles::new());
+
+        width
+    } else {
+        0
+    }
+}
+
+// returns width used
+fn print_timeline(out: &mut FrameBuffer, state: &State, x: i32, h: i32) -> i32 {
+    if state.timeline_tick > 0 {
+        let gray = Terminal::grayscale_u8(12);
+        let text = format!("[T{}]", state.timeline_tick);
+        let width = text.len() as i32 + 1;
+
+        out.write_line(x, h - 1, text, texel_types::DEFAULT_BG_U8, gray, SymbolStyles::new());
+
+        width
+    } else {
+        0
+    }
+}
+
+// returns width used, autosave is off until the swap is dealt with
+fn print_stale_swap(out: &mut FrameBuffer, state: &State, x: i32, h: i32) -> i32 {
+    if state.stale_swap() {
+        let red = Terminal::rgb_u8(5, 0, 0);
+        let text = "[SWAP]";
+
+        out.write_line(x, h - 1, text, texel_types::DEFAULT_BG_U8, red, SymbolStyles::new());
+
+        text.len() as i32 + 1
+    } else {
+        0
+    }
+}
+
+fn print_preview(out: &mut FrameBuffer, state: &State, x: i32, h: i32) {
+    if let Some(depth) = state.preview {
+        let gray = Terminal::grayscale_u8(12);
+
+        out.write_line(
+            x,
+            h - 1,
+            format!("[P{}]", depth.as_str()),
+            texel_types::DEFAULT_BG_U8,
+            gray,
+            SymbolStyles::new(),
+        );
+    }
+}
+
 fn print_mode(out: &mut FrameBuffer, state: &State, mode: Mode, w: i32, h: i32) {
     let white = Terminal::grayscale_u8(23);
     let bold = SymbolStyles::only(SymbolStyle::Bold);
     let text = format!("--{}--", mode.as_str());
 
+    let text_len = text.chars().count() as i32;
+
     out.write_line(0, h - 1, text, texel_types::DEFAULT_BG_U8, white, bold);
+    let group_len = print_group(out, state, text_len + 1, h);
+    let timeline_len = print_timeline(out, state, text_len + 1 + group_len, h);
+    let swap_len = print_stale_swap(out, state, text_len + 1 + group_len + timeline_len, h);
+    print_preview(out, state, text_len + 1 + group_len + timeline_len + swap_len, h);
     if mode == Mode::Object(SelectMode::Region) {
         out.set_cursor_pos(state.cursor.x, state.cursor.y);
     } else {
@@ -89,12 +171,28 @@ fn print_mode(out: &mut FrameBuffer, state: &State, mode: Mode, w: i32, h: i32)
     }
 }
 
+fn print_play(out: &mut FrameBuffer, state: &State, w: i32, h: i32) {
+    let white = Terminal::grayscale_u8(23);
+    let bold = SymbolStyles::only(SymbolStyle::Bold);
+    let playback = &state.playback;
+    let text = format!(
+        "--PLAY-- {}fps {}",
+        playback.fps,
+        if playback.ping_pong { "pingpong" } else { "loop" }
+    );
+
+    out.write_line(0, h - 1, text, texel_types::DEFAULT_BG_U8, white, bold);
+    out.set_cursor_pos(w - 1, h - 1);
+}
+
 fn print_edit(out: &mut FrameBuffer, state: &State, palette: &SymbolPalette, h: i32) {
     let white = Terminal::grayscale_u8(23);
     let bold = SymbolStyles::only(SymbolStyle::Bold);
 
     out.write_line(0, h - 1, "--EDIT--", texel_types::DEFAULT_BG_U8, white, bold);
+    print_symmetry(out, state, 9, h);
     out.write_texels(palette.line_texels(PALETTE_OFFSET, h - 1));
+    print_page(out, palette.page(), palette.page_count(), PALETTE_OFFSET + 32, h);
     out.set_cursor_pos(state.cursor.x, state.cursor.y);
 }
 
@@ -105,9 +203,23 @@ fn print_color_select(out: &mut FrameBuffer, state: &State, palette: &ColorPalet
 
     out.write_line(0, h - 1, text, texel_types::DEFAULT_BG_U8, white, bold);
     out.write_texels(palette.line_texels(PALETTE_OFFSET, h - 1, cm));
+    print_page(out, palette.page(), palette.page_count(), PALETTE_OFFSET + 17, h);
     out.set_cursor_pos(w - 1, h - 1);
 }
 
+fn print_page(out: &mut FrameBuffer, page: usize, count: usize, x: i32, h: i32) {
+    let gray = Terminal::grayscale_u8(12);
+
+    out.write_line(
+        x,
+        h - 1,
+        format!("[{}/{}]", page + 1, count),
+        texel_types::DEFAULT_BG_U8,
+        gray,
+        SymbolStyles::new(),
+    );
+}
+
 fn print_symbol_palette(out: &mut FrameBuffer, state: &State, palette: &SymbolPalette, index: usize, w: i32, h: i32) {
     let white = Terminal::grayscale_u8(23);
     let bold = SymbolStyles::only(SymbolStyle::Bold);
@@ -133,34 +245,26 @@ fn print_color_palette(
     cm: ColorMode,
     h: i32,
 ) {
-    use crate::resources::{MAX_COLOR_INDEX, PALETTE_H, PALETTE_W};
+    use crate::common::Rgb;
+    use crate::resources::{PALETTE_H, PALETTE_W};
 
-    let mut count = 0;
     let white = Terminal::grayscale_u8(23);
     let bold = SymbolStyles::only(SymbolStyle::Bold);
     let text = format!("--{}--", state.mode().as_str());
-    let min = Position2D {
-        x: PALETTE_OFFSET,
-        y: h - PALETTE_H,
-    };
-
-    for y in min.y..min.y + PALETTE_H - 1 {
-        for x in min.x..min.x + PALETTE_W {
-            if count >= MAX_COLOR_INDEX {
-                break;
-            }
+    let min = ColorPalette::picker_pos();
 
-            let (r, g, b) = ColorPalette::base_to_rgb(count);
-            count += 1;
-
-            out.write_line(
-                x,
-                y,
-                " ",
-                Terminal::rgb_u8(r, g, b),
-                texel_types::DEFAULT_FG_U8,
-                SymbolStyles::new(),
-            );
+    for y in 0..PALETTE_H - 1 {
+        for x in 0..PALETTE_W {
+            if let Some(color) = ColorPalette::picker_color(Position2D { x, y }) {
+                out.write_line(
+                    min.x + x,
+                    min.y + y,
+                    " ",
+                    color,
+                    texel_types::DEFAULT_FG_U8,
+                    SymbolStyles::new(),
+                );
+            }
         }
     }
 
@@ -169,5 +273,18 @@ fn print_color_palette(
 
     out.write_line(1, h - 1, text, texel_types::DEFAULT_BG_U8, white, bold);
     out.write_texel(palette.selector_texel(index, pos, cm));
+
+    // index readout of the color under cursor
+    if let Some(color) = ColorPalette::pos_to_color(state.cursor) {
+        let readout = format!("{:3} {}", color, Rgb::from_ansi(color));
+        out.write_line(
+            PALETTE_OFFSET + 17,
+            h - 1,
+            readout,
+            texel_types::DEFAULT_BG_U8,
+            white,
+            bold,
+        );
+    }
     out.set_cursor_pos(state.cursor.x, state.cursor.y);
 }
@@ -0,0 +1,56 @@
+use crate::os::Terminal;
+use crate::resources::{FrameBuffer, State};
+use texel_types::{Position2D, SymbolStyles, Texel, DEFAULT_FG_U8};
+
+// draws grid lines as background, sprites render over it
+pub fn render_grid(state: &State, out: &mut FrameBuffer) {
+    if !state.grid.visible || state.hides_scene() {
+        return;
+    }
+
+    let ts = Terminal::terminal_size();
+    let offset = state.offset();
+
+    for y in 0..i32::from(ts.1) - 1 {
+        for x in 0..i32::from(ts.0) {
+            let pos = Position2D { x, y };
+
+            if state.grid.on_line(pos + offset) {
+                out.write_texel(Texel {
+                    pos,
+                    symbol: ' ',
+                    bg: state.grid.color,
+                    fg: DEFAULT_FG_U8,
+                    styles: SymbolStyles::new(),
+                });
+            }
+        }
+    }
+}
+
+pub fn render_guides(state: &State, out: &mut FrameBuffer) {
+    if state.guides.is_empty() || state.hides_scene() {
+        return;
+    }
+
+    let ts = Terminal::terminal_size();
+    let offset = state.offset();
+    let color = Terminal::rgb_u8(0, 1, 2);
+
+    for y in 0..i32::from(ts.1) - 1 {
+        for x in 0..i32::from(ts.0) {
+            let pos = Position2D { x, y };
+            let abs_pos = pos + offset;
+
+            if state.guides.columns.contains(&abs_pos.x) || state.guides.rows.contains(&abs_pos.y) {
+                out.write_texel(Texel {
+                    pos,
+                    symbol: ' ',
+                    bg: color,
+                    fg: DEFAULT_FG_U8,
+                    styles: SymbolStyles::new(),
+                });
+            }
+        }
+    }
+}
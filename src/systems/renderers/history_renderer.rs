@@ -0,0 +1,55 @@
+use crate::os::Terminal;
+use crate::resources::{FrameBuffer, State};
+use std::time::SystemTime;
+use texel_types::SymbolStyles;
+
+// undo tree view instead of the scene, one state per row
+pub fn render_history(state: &State, out: &mut FrameBuffer) {
+    let selected = match state.show_history() {
+        Some(id) => id,
+        None => return,
+    };
+
+    let ts = Terminal::terminal_size();
+    let w = i32::from(ts.0);
+    let rows = usize::from(ts.1).saturating_sub(1); // cmdline
+
+    let list = state.history_list();
+    let row = list.iter().position(|item| item.id == selected).unwrap_or_default();
+    let start = (row + 1).saturating_sub(rows); // keep selected row visible
+
+    let highlight = Terminal::grayscale_u8(6);
+    let white = Terminal::grayscale_u8(23);
+
+    for (y, item) in list.iter().skip(start).take(rows).enumerate() {
+        let marker = if item.current { '>' } else { ' ' };
+        let text = format!(
+            "{} {:indent$}{:<5} {:>8}  {}",
+            marker,
+            "",
+            item.id,
+            elapsed_str(item.time),
+            item.label,
+            indent = item.depth * 2,
+        );
+        let text: String = text.chars().take(w.max(0) as usize).collect();
+
+        if item.id == selected {
+            let padded = format!("{:width$}", text, width = w.max(0) as usize);
+            out.write_line(0, y as i32, padded, highlight, white, SymbolStyles::new());
+        } else {
+            out.write_line_default(0, y as i32, text);
+        }
+    }
+}
+
+fn elapsed_str(time: SystemTime) -> String {
+    let secs = time.elapsed().map(|d| d.as_secs()).unwrap_or_default();
+
+    match secs {
+        0..=59 => format!("{}s ago", secs),
+        60..=3599 => format!("{}m ago", secs / 60),
+        3600..=86399 => format!("{}h ago", secs / 3600),
+        _ => format!("{}d ago", secs / 86400),
+    }
+}
@@ -1,4 +1,4 @@
-use crate::common::{index_from_one, shortened_str};
+use crate::common::{index_from_one, shortened_str, Animation};
 use crate::components::{Bookmark, Selection, Sprite};
 use crate::os::Terminal;
 use crate::resources::{FrameBuffer, State};
@@ -7,7 +7,7 @@ use legion::*;
 const METADATA_WIDTH: i32 = 25;
 
 pub fn render_meta_info(world: &mut World, state: &State, out: &mut FrameBuffer) {
-    if !state.show_meta {
+    if !state.show_meta || state.show_history().is_some() {
         return;
     }
 
@@ -25,8 +25,8 @@ pub fn render_meta_info(world: &mut World, state: &State, out: &mut FrameBuffer)
 
     let x = w - METADATA_WIDTH;
     let mut y = 0i32;
-    let mut query = <Read<Sprite>>::query().filter(component::<Selection>());
-    for sprite in query.iter(world) {
+    let mut query = <(Read<Sprite>, TryRead<Animation>)>::query().filter(component::<Selection>());
+    for (sprite, animation) in query.iter(world) {
         match sprite.id {
             Some(id) => out.write_line_default(x, y, format!("===  {}  ===", id)),
             None => out.write_line_default(x, y, "===<NONE>==="),
@@ -47,5 +47,27 @@ pub fn render_meta_info(world: &mut World, state: &State, out: &mut FrameBuffer)
         }
 
         y += 1;
+
+        if let Some(animation) = animation {
+            let duration = match animation.duration(sprite.index) {
+                Some(ms) => format!("{}ms", ms),
+                None => String::from("-"),
+            };
+            out.write_line_default(
+                x,
+                y,
+                format!(" Frame: {} Loop: {}", duration, animation.loop_mode.as_str()),
+            );
+            y += 1;
+
+            let ranges = animation.ranges_str();
+            let ranges_str = shortened_str(&ranges, 16);
+            if ranges_str.1 {
+                out.write_line_default(x, y, format!(" Anims: {}...", ranges_str.0));
+            } else {
+                out.write_line_default(x, y, format!(" Anims: {}", ranges_str.0));
+            }
+            y += 1;
+        }
     }
 }
@@ -0,0 +1,76 @@
+use crate::common::Mode;
+use crate::os::Terminal;
+use crate::resources::{FrameBuffer, State};
+use texel_types::{Position2D, SymbolStyles, Texel};
+
+const LEFT_RULER_W: i32 = 4;
+
+// crosshair and rulers go over sprites
+pub fn render_rulers(state: &State, out: &mut FrameBuffer) {
+    if state.hides_scene() {
+        return;
+    }
+
+    let ts = Terminal::terminal_size();
+    let w = i32::from(ts.0);
+    let h = i32::from(ts.1) - 1; // cmdline
+
+    if state.crosshair && state.mode() == Mode::Edit {
+        print_crosshair(out, state, w, h);
+    }
+
+    if state.rulers {
+        print_rulers(out, state, w, h);
+    }
+}
+
+fn print_crosshair(out: &mut FrameBuffer, state: &State, w: i32, h: i32) {
+    let color = Terminal::grayscale_u8(4);
+    let cursor = state.cursor;
+    let horizontal = (0..w).map(|x| Position2D { x, y: cursor.y });
+    let vertical = (0..h).map(|y| Position2D { x: cursor.x, y });
+
+    for pos in horizontal.chain(vertical).filter(|pos| *pos != cursor) {
+        out.override_texel_bg(Texel {
+            pos,
+            symbol: ' ',
+            bg: color,
+            fg: color,
+            styles: SymbolStyles::new(),
+        });
+    }
+}
+
+fn print_rulers(out: &mut FrameBuffer, state: &State, w: i32, h: i32) {
+    let bg = Terminal::grayscale_u8(2);
+    let fg = Terminal::grayscale_u8(14);
+    let offset = state.offset();
+    let styles = SymbolStyles::new();
+
+    // top, labels every 10 columns
+    for x in 0..w {
+        let abs_x = x + offset.x;
+        let symbol = if abs_x.rem_euclid(5) == 0 { '|' } else { '·' };
+
+        out.write_line(x, 0, symbol, bg, fg, styles);
+    }
+    for x in 0..w {
+        let abs_x = x + offset.x;
+
+        if abs_x.rem_euclid(10) == 0 {
+            out.write_line(x, 0, abs_x, bg, fg, styles);
+        }
+    }
+
+    // left, labels every 5 rows
+    for y in 1..h {
+        let abs_y = y + offset.y;
+        let text = if abs_y.rem_euclid(5) == 0 {
+            format!("{:>width$}", abs_y, width = LEFT_RULER_W as usize)
+        } else {
+            format!("{:>width$}", '·', width = LEFT_RULER_W as usize)
+        };
+
+        out.write_line(0, y, text, bg, fg, styles);
+    }
+}
@@ -1,5 +1,7 @@
-use crate::common::{scene_for_help_index, Scene, SelectedInfo};
-use crate::components::{Dimension, Position, Position2D, Selection, Sprite};
+use crate::common::{
+    scene_for_help_index, Animation, ExtStyleMap, ExtStyles, Keyframes, Mode, OnionSkin, Scene, SelectedInfo,
+};
+use crate::components::{Dimension, Hidden, Position, Position2D, Selection, Sprite};
 use crate::os::Terminal;
 use crate::resources::{FrameBuffer, State};
 use legion::*;
@@ -11,8 +13,21 @@ pub fn render_sprites(world: &mut World, state: &mut State, out: &mut FrameBuffe
         return; // show help, done
     }
 
+    if state.show_history().is_some() {
+        return; // undo tree view instead
+    }
+
     let mut selected_info = SelectedInfo::from(state.offset());
-    let mut query = <(Read<Position>, Read<Dimension>, Read<Sprite>, TryRead<Selection>)>::query();
+    let mut query = <(
+        Read<Position>,
+        Read<Dimension>,
+        Read<Sprite>,
+        TryRead<Selection>,
+        TryRead<Keyframes>,
+        TryRead<Animation>,
+        TryRead<ExtStyleMap>,
+    )>::query()
+    .filter(!component::<Hidden>());
 
     // TODO: optimize
     let mut sorted = Vec::new();
@@ -20,10 +35,31 @@ pub fn render_sprites(world: &mut World, state: &mut State, out: &mut FrameBuffe
     for tuple in query.iter(world) {
         sorted.push(tuple);
     }
-    sorted.sort_by(|a, b| b.0.z.cmp(&a.0.z));
+    sorted.sort_by_key(|s| std::cmp::Reverse(s.0.z));
+    let playing = state.mode() == Mode::Play;
+
+    for (pos, dim, sprite, is_selected, keyframes, animation, ext_styles) in sorted {
+        // the timeline moves every keyed object, sprite frames play only for selected or all
+        let timeline_pos = match keyframes {
+            Some(keyframes) if playing => keyframes.at(state.playback.timeline_tick()),
+            _ => None,
+        };
+        let pos = timeline_pos.as_ref().unwrap_or(pos);
+
+        if playing && (state.playback.all || is_selected.is_some()) {
+            let (index, _) = state.playback.frame(sprite.frame_count(), animation);
+
+            render_frame(out, state, pos, sprite, index, ext_styles);
+            continue;
+        }
 
-    for (pos, dim, sprite, is_selected) in sorted {
-        render_sprite(out, state, pos, sprite);
+        if is_selected.is_some() {
+            if let Some(onion_skin) = state.onion_skin() {
+                render_onion_skin(out, state, pos, sprite, onion_skin);
+            }
+        }
+
+        render_sprite(out, state, pos, sprite, ext_styles);
 
         if is_selected.is_some() {
             render_border(out, state, pos, *dim);
@@ -40,27 +76,69 @@ pub fn render_sprites(world: &mut World, state: &mut State, out: &mut FrameBuffe
 
 fn render_scene(out: &mut FrameBuffer, state: &State, scene: Scene) {
     for obj in scene.current().objects {
-        render_sprite(out, state, &obj.1, &obj.0);
+        render_sprite(out, state, &obj.1, &obj.0, None);
     }
 }
 
-fn render_sprite(out: &mut FrameBuffer, state: &State, p: &Position, s: &Sprite) {
-    for t in s.frame_iter() {
-        print_texel(out, state, p, t);
+fn render_sprite(out: &mut FrameBuffer, state: &State, p: &Position, s: &Sprite, ext_styles: Option<&ExtStyleMap>) {
+    render_frame(out, state, p, s, s.index, ext_styles);
+}
+
+fn render_frame(
+    out: &mut FrameBuffer,
+    state: &State,
+    p: &Position,
+    s: &Sprite,
+    index: usize,
+    ext_styles: Option<&ExtStyleMap>,
+) {
+    if let Some(frame) = s.frames.get(index) {
+        for t in frame.iter() {
+            let styles = ext_styles.map(|map| map.get(index, t.pos)).unwrap_or_default();
+
+            print_styled_texel(out, state, p, t, styles);
+        }
+    }
+}
+
+// neighbouring frames in a single dimmed color under the edited one, render only
+fn render_onion_skin(out: &mut FrameBuffer, state: &State, p: &Position, s: &Sprite, onion_skin: OnionSkin) {
+    for (index, distance, next) in onion_skin.frames(s.index, s.frame_count()) {
+        let color = onion_skin.color(distance, next);
+
+        for t in s.frames[index].iter() {
+            let dimmed = Texel {
+                pos: t.pos,
+                symbol: t.symbol,
+                bg: DEFAULT_BG_U8,
+                fg: color,
+                styles: SymbolStyles::new(),
+            };
+
+            print_texel(out, state, p, &dimmed);
+        }
     }
 }
 
 fn print_texel(out: &mut FrameBuffer, state: &State, p: &Position, t: &Texel) {
+    print_styled_texel(out, state, p, t, ExtStyles::new());
+}
+
+fn print_styled_texel(out: &mut FrameBuffer, state: &State, p: &Position, t: &Texel, ext_styles: ExtStyles) {
     let pos2d: Position2D = (*p + t.pos).into();
+    let (bg, fg) = match state.preview {
+        Some(depth) => (depth.quantize(t.bg, &state.colors), depth.quantize(t.fg, &state.colors)),
+        None => (t.bg, t.fg),
+    };
     let abs_texel = Texel {
         pos: pos2d - state.offset(),
         symbol: t.symbol,
-        bg: t.bg,
-        fg: t.fg,
+        bg,
+        fg,
         styles: t.styles,
     };
 
-    out.write_texel(abs_texel);
+    out.write_styled_texel(abs_texel, ext_styles);
 }
 
 fn render_border(out: &mut FrameBuffer, state: &State, p: &Position, d: Dimension) {
@@ -1,5 +1,5 @@
 use crate::common::{Mode, SelectMode};
-use crate::components::{Dimension, Position2D, Subselection};
+use crate::components::{Dimension, Position2D, SelectionMask, Subselection};
 use crate::resources::{ColorPalette, FrameBuffer, State};
 use legion::*;
 use texel_types::{SymbolStyles, Texel};
@@ -27,4 +27,19 @@ pub fn render_subselections(world: &mut World, state: &mut State, out: &mut Fram
             });
         }
     }
+
+    // texel level selections are in absolute coordinates
+    let mut query = <Read<SelectionMask>>::query();
+
+    for mask in query.iter(world) {
+        for pos in mask.0.iter() {
+            out.override_texel_bg(Texel {
+                pos: *pos - state.offset(),
+                symbol: ' ',
+                bg: select_color,
+                fg: texel_types::DEFAULT_FG_U8,
+                styles: SymbolStyles::new(),
+            });
+        }
+    }
 }